@@ -0,0 +1,249 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+///distribution module to handle
+/// pools of an asset shared out to the holders of the share asset (RKX), pro rata to their balance
+/// the holder base is fixed by a snapshot of the share asset taken when the pool is created
+/// creating a pool costs a deposit of the share asset, handed back with what is left of the pool once it expires
+/// each holder claims their own share until the pool expires, nothing iterates over holders
+/// pooled funds and deposits stay in a reserve of the creator tagged with the distribution reserve id
+use codec::{Decode, Encode};
+use frame_support::{decl_error, decl_event, decl_module, decl_storage, dispatch::DispatchResult, ensure, traits::Get};
+use payment_token::SnapshotId;
+use sp_runtime::{
+	traits::{CheckedAdd, SaturatedConversion, Saturating, Zero},
+	RuntimeDebug,
+};
+use sp_std::prelude::*;
+use system::ensure_signed;
+
+#[cfg(all(feature = "std", test))]
+mod mock;
+
+#[cfg(all(feature = "std", test))]
+mod tests;
+
+pub type DistributionId = u64;
+
+type AssetIdOf<T> = <T as payment_token::Trait>::AssetId;
+type BalanceOf<T> = <T as payment_token::Trait>::Balance;
+
+/// Fixed point scale of the reward per token.
+pub const REWARD_PRECISION: u128 = 1_000_000_000_000;
+
+/// The pallet's configuration trait.
+pub trait Trait: payment_token::Trait {
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+	/// The asset whose holders share the distributions.
+	type ShareAssetId: Get<AssetIdOf<Self>>;
+	/// Tag of the payment token reserve pooled funds are held in.
+	type DistributionReserveId: Get<<Self as payment_token::Trait>::ReserveIdentifier>;
+	/// Amount of the share asset held from the creator of a distribution until it is reclaimed, as every
+	/// distribution takes a snapshot of the share asset all later transfers pay for.
+	type CreationDeposit: Get<BalanceOf<Self>>;
+	/// Number of blocks holders have to claim their share, after which the creator takes back what is left.
+	type ClaimPeriod: Get<Self::BlockNumber>;
+}
+
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Distribution<AccountId, AssetId, Balance, BlockNumber> {
+	pub creator: AccountId,
+	/// The asset paid out.
+	pub asset_id: AssetId,
+	pub amount: Balance,
+	/// Amount of the share asset held from the creator.
+	pub deposit: Balance,
+	/// Claims are accepted before this block, from then on the creator may reclaim what is left.
+	pub expiry: BlockNumber,
+	/// Snapshot of the share asset holders are paid according to.
+	pub snapshot_id: SnapshotId,
+	/// Amount paid out per unit of the share asset, scaled by `REWARD_PRECISION`.
+	pub reward_per_token: u128,
+	/// Amount claimed so far.
+	pub claimed: Balance,
+}
+
+type DistributionOf<T> =
+	Distribution<<T as system::Trait>::AccountId, AssetIdOf<T>, BalanceOf<T>, <T as system::Trait>::BlockNumber>;
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Distribution {
+		NextDistributionId get(next_distribution_id): DistributionId;
+		Distributions get(distribution): map hasher(blake2_256) DistributionId => Option<DistributionOf<T>>;
+		/// Whether an account claimed its share of a distribution.
+		Claimed get(claimed): double_map hasher(blake2_256) DistributionId, blake2_256(T::AccountId) => bool;
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event() = default;
+
+		/// Share `amount` of the sender's free balance out to the current holders of the share asset.
+		fn create_distribution(origin, asset_id: AssetIdOf<T>, amount: BalanceOf<T>) -> DispatchResult {
+			let creator = ensure_signed(origin)?;
+			ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
+
+			let share_asset_id = T::ShareAssetId::get();
+			let total_shares: u128 = <payment_token::Module<T>>::total_supply(&share_asset_id).saturated_into();
+			ensure!(total_shares > 0, Error::<T>::NoHolders);
+			let reward_per_token = amount.saturated_into::<u128>()
+				.checked_mul(REWARD_PRECISION)
+				.ok_or(Error::<T>::AmountTooHigh)? / total_shares;
+
+			let distribution_id = Self::next_distribution_id();
+			let next_distribution_id = distribution_id.checked_add(1).ok_or(Error::<T>::NoAvailableDistributionId)?;
+			<payment_token::Module<T>>::current_snapshot(&share_asset_id)
+				.checked_add(1).ok_or(Error::<T>::NoAvailableSnapshotId)?;
+			let expiry = <system::Module<T>>::block_number() + T::ClaimPeriod::get();
+
+			// both the deposit and the pool are checked before anything is held, as is the snapshot, so a
+			// failing call takes no snapshot
+			let deposit = T::CreationDeposit::get();
+			if asset_id == share_asset_id {
+				let total = amount.checked_add(&deposit).ok_or(Error::<T>::AmountTooHigh)?;
+				<payment_token::Module<T>>::ensure_can_reserve(&asset_id, &creator, total)?;
+			} else {
+				<payment_token::Module<T>>::ensure_can_reserve(&share_asset_id, &creator, deposit)?;
+				<payment_token::Module<T>>::ensure_can_reserve(&asset_id, &creator, amount)?;
+			}
+
+			let reserve_id = T::DistributionReserveId::get();
+			<payment_token::Module<T>>::do_reserve(&share_asset_id, &creator, &reserve_id, deposit)?;
+			<payment_token::Module<T>>::do_reserve(&asset_id, &creator, &reserve_id, amount)?;
+			let snapshot_id = <payment_token::Module<T>>::do_snapshot(&share_asset_id)?;
+
+			<Distributions<T>>::insert(distribution_id, Distribution {
+				creator: creator.clone(),
+				asset_id,
+				amount,
+				deposit,
+				expiry,
+				snapshot_id,
+				reward_per_token,
+				claimed: Zero::zero(),
+			});
+			NextDistributionId::put(next_distribution_id);
+
+			Self::deposit_event(RawEvent::DistributionCreated(distribution_id, creator, asset_id, amount));
+			Ok(())
+		}
+
+		/// Pay the sender their share of a distribution, according to their balance of the share asset
+		/// when it was created. A share too small to open the sender's account of the paid asset is
+		/// forfeited and left in the pool for the creator to reclaim.
+		fn claim(origin, distribution_id: DistributionId) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let mut distribution = Self::distribution(distribution_id).ok_or(Error::<T>::DistributionNotExist)?;
+			ensure!(<system::Module<T>>::block_number() < distribution.expiry, Error::<T>::ClaimPeriodOver);
+			ensure!(!Self::claimed(distribution_id, &sender), Error::<T>::AlreadyClaimed);
+
+			let payout = Self::share_of(&distribution, &sender);
+			ensure!(!payout.is_zero(), Error::<T>::NothingToClaim);
+
+			if <payment_token::Module<T>>::ensure_can_create(&distribution.asset_id, &sender, payout).is_err() {
+				<Claimed<T>>::insert(distribution_id, &sender, true);
+				Self::deposit_event(RawEvent::ShareForfeited(distribution_id, sender, payout));
+				return Ok(());
+			}
+
+			<payment_token::Module<T>>::do_repatriate_reserved(
+				&distribution.asset_id,
+				&distribution.creator,
+				&T::DistributionReserveId::get(),
+				&sender,
+				payout,
+			)?;
+			<Claimed<T>>::insert(distribution_id, &sender, true);
+			distribution.claimed = distribution.claimed.saturating_add(payout);
+			<Distributions<T>>::insert(distribution_id, distribution);
+
+			Self::deposit_event(RawEvent::Claimed(distribution_id, sender, payout));
+			Ok(())
+		}
+
+		/// Once a distribution expired, hand what is left of its pool, unclaimed and forfeited shares as well as
+		/// rounding leftovers, back to the creator along with the deposit, and remove the distribution.
+		fn reclaim(origin, distribution_id: DistributionId) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let distribution = Self::distribution(distribution_id).ok_or(Error::<T>::DistributionNotExist)?;
+			ensure!(sender == distribution.creator, Error::<T>::NotCreator);
+			ensure!(<system::Module<T>>::block_number() >= distribution.expiry, Error::<T>::ClaimPeriodNotOver);
+
+			// both unreserves are checked before either is made, so that a failing second one can't leave the
+			// pool handed back while the distribution stays around to be reclaimed again
+			let share_asset_id = T::ShareAssetId::get();
+			let reserve_id = T::DistributionReserveId::get();
+			let leftover = distribution.amount.saturating_sub(distribution.claimed);
+			if distribution.asset_id == share_asset_id {
+				let total = leftover.checked_add(&distribution.deposit).ok_or(Error::<T>::AmountTooHigh)?;
+				<payment_token::Module<T>>::ensure_can_unreserve(&share_asset_id, &sender, &reserve_id, total)?;
+			} else {
+				<payment_token::Module<T>>::ensure_can_unreserve(&distribution.asset_id, &sender, &reserve_id, leftover)?;
+				<payment_token::Module<T>>::ensure_can_unreserve(&share_asset_id, &sender, &reserve_id, distribution.deposit)?;
+			}
+
+			if !leftover.is_zero() {
+				<payment_token::Module<T>>::do_unreserve(&distribution.asset_id, &sender, &reserve_id, leftover)?;
+			}
+			if !distribution.deposit.is_zero() {
+				<payment_token::Module<T>>::do_unreserve(&share_asset_id, &sender, &reserve_id, distribution.deposit)?;
+			}
+			// claims of the distribution are left in place, clearing them would take a write per claimant
+			<Distributions<T>>::remove(distribution_id);
+
+			Self::deposit_event(RawEvent::Reclaimed(distribution_id, leftover));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// What `who` is owed by the distribution, whether claimed or not.
+	pub fn share_of(distribution: &DistributionOf<T>, who: &T::AccountId) -> BalanceOf<T> {
+		let balance = <payment_token::Module<T>>::balance_at(T::ShareAssetId::get(), who.clone(), distribution.snapshot_id)
+			.unwrap_or_default();
+		let share = balance.saturated_into::<u128>().saturating_mul(distribution.reward_per_token) / REWARD_PRECISION;
+		share.saturated_into()
+	}
+}
+
+decl_event!(
+	pub enum Event<T>
+	where
+		AccountId = <T as system::Trait>::AccountId,
+		AssetId = AssetIdOf<T>,
+		Balance = BalanceOf<T>,
+	{
+		/// A distribution was created (distribution id, creator, asset, amount).
+		DistributionCreated(DistributionId, AccountId, AssetId, Balance),
+		/// A holder claimed their share (distribution id, holder, amount).
+		Claimed(DistributionId, AccountId, Balance),
+		/// A share too small to open the holder's account was left in the pool (distribution id, holder, amount).
+		ShareForfeited(DistributionId, AccountId, Balance),
+		/// The creator took back what was left of an expired distribution (distribution id, amount).
+		Reclaimed(DistributionId, Balance),
+	}
+);
+
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		ZeroAmount,
+		/// The share asset has no supply to share out to
+		NoHolders,
+		/// The amount can't be shared out without overflow
+		AmountTooHigh,
+		NoAvailableDistributionId,
+		/// No more snapshots of the share asset can be taken
+		NoAvailableSnapshotId,
+		DistributionNotExist,
+		AlreadyClaimed,
+		/// The sender held none of the share asset at the snapshot
+		NothingToClaim,
+		/// The distribution expired, what is left of it goes back to the creator
+		ClaimPeriodOver,
+		/// Only the creator may reclaim a distribution
+		NotCreator,
+		/// Holders may still claim their share
+		ClaimPeriodNotOver,
+	}
+}
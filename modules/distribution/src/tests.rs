@@ -0,0 +1,139 @@
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::{build_ext, DistributionModule, Origin, PaymentToken, System, TestRuntime};
+
+const DIVIDEND: [u8; 8] = *b"dividend";
+
+#[test]
+fn create_distribution_ok() {
+	build_ext().execute_with(|| {
+		assert_noop!(DistributionModule::create_distribution(Origin::signed(1), 1, 0), Error::<TestRuntime>::ZeroAmount);
+		assert_noop!(
+			DistributionModule::create_distribution(Origin::signed(2), 1, 1000),
+			payment_token::Error::<TestRuntime>::AmountTooLow
+		);
+		//the deposit has to be paid in the share asset, account 4 has none
+		assert_ok!(PaymentToken::transfer_from(Origin::signed(1), 1, 4, 1000));
+		assert_noop!(
+			DistributionModule::create_distribution(Origin::signed(4), 1, 1000),
+			payment_token::Error::<TestRuntime>::AmountTooLow
+		);
+
+		assert_ok!(DistributionModule::create_distribution(Origin::signed(1), 1, 1000));
+		assert_eq!(DistributionModule::next_distribution_id(), 1);
+		let distribution = DistributionModule::distribution(0).unwrap();
+		assert_eq!(distribution.snapshot_id, PaymentToken::current_snapshot(0));
+		assert_eq!(distribution.reward_per_token, REWARD_PRECISION);
+		assert_eq!(distribution.expiry, 101);
+		//the pool and the deposit are held in the dividend reserve of the creator
+		assert_eq!(PaymentToken::named_reserve_of(1, (1, DIVIDEND)), 1000);
+		assert_eq!(PaymentToken::free_balance(1, 1), 1000000 - 2000);
+		assert_eq!(PaymentToken::named_reserve_of(0, (1, DIVIDEND)), 10);
+		assert_eq!(PaymentToken::free_balance(0, 1), 590);
+	})
+}
+
+#[test]
+fn create_distribution_of_share_asset_ok() {
+	build_ext().execute_with(|| {
+		//the pool and the deposit together must fit the free balance
+		assert_noop!(
+			DistributionModule::create_distribution(Origin::signed(1), 0, 591),
+			payment_token::Error::<TestRuntime>::AmountTooLow
+		);
+		assert_ok!(DistributionModule::create_distribution(Origin::signed(1), 0, 590));
+		assert_eq!(PaymentToken::named_reserve_of(0, (1, DIVIDEND)), 600);
+	})
+}
+
+#[test]
+fn claim_ok() {
+	build_ext().execute_with(|| {
+		assert_noop!(DistributionModule::claim(Origin::signed(2), 0), Error::<TestRuntime>::DistributionNotExist);
+		assert_ok!(DistributionModule::create_distribution(Origin::signed(1), 1, 1000));
+
+		assert_ok!(DistributionModule::claim(Origin::signed(2), 0));
+		assert_eq!(PaymentToken::free_balance(1, 2), 300);
+		assert_noop!(DistributionModule::claim(Origin::signed(2), 0), Error::<TestRuntime>::AlreadyClaimed);
+		assert_noop!(DistributionModule::claim(Origin::signed(4), 0), Error::<TestRuntime>::NothingToClaim);
+
+		//the creator claims their own share too
+		assert_ok!(DistributionModule::claim(Origin::signed(1), 0));
+		assert_ok!(DistributionModule::claim(Origin::signed(3), 0));
+		assert_eq!(PaymentToken::free_balance(1, 3), 100);
+		assert_eq!(PaymentToken::free_balance(1, 1), 1000000 - 400);
+		assert_eq!(PaymentToken::reserved_balance(1, 1), 0);
+		assert_eq!(DistributionModule::distribution(0).unwrap().claimed, 1000);
+	})
+}
+
+#[test]
+fn claim_uses_snapshot_ok() {
+	build_ext().execute_with(|| {
+		assert_ok!(DistributionModule::create_distribution(Origin::signed(1), 1, 1000));
+
+		//moving shares after the distribution was created doesn't move the claim
+		assert_ok!(PaymentToken::transfer_from(Origin::signed(2), 0, 4, 300));
+		assert_noop!(DistributionModule::claim(Origin::signed(4), 0), Error::<TestRuntime>::NothingToClaim);
+		assert_ok!(DistributionModule::claim(Origin::signed(2), 0));
+		assert_eq!(PaymentToken::free_balance(1, 2), 300);
+
+		//a later distribution follows the new balances
+		assert_ok!(DistributionModule::create_distribution(Origin::signed(1), 1, 1000));
+		assert_noop!(DistributionModule::claim(Origin::signed(2), 1), Error::<TestRuntime>::NothingToClaim);
+		assert_ok!(DistributionModule::claim(Origin::signed(4), 1));
+		assert_eq!(PaymentToken::free_balance(1, 4), 300);
+	})
+}
+
+#[test]
+fn claim_rounds_down_ok() {
+	build_ext().execute_with(|| {
+		assert_ok!(DistributionModule::create_distribution(Origin::signed(1), 1, 15));
+		assert_ok!(DistributionModule::claim(Origin::signed(2), 0));
+		assert_ok!(DistributionModule::claim(Origin::signed(3), 0));
+
+		//4.5 and 1.5 are rounded down, so claims never exceed the pool
+		assert_eq!(PaymentToken::free_balance(1, 2), 4);
+		assert_eq!(PaymentToken::free_balance(1, 3), 1);
+	})
+}
+
+#[test]
+fn claim_below_minimum_balance_forfeited() {
+	build_ext().execute_with(|| {
+		assert_ok!(PaymentToken::set_minimum_balance(Origin::signed(1), 1, 200));
+		assert_ok!(DistributionModule::create_distribution(Origin::signed(1), 1, 1000));
+
+		//100 can't open the account of holder 3, the share stays in the pool
+		assert_ok!(DistributionModule::claim(Origin::signed(3), 0));
+		assert_eq!(PaymentToken::free_balance(1, 3), 0);
+		assert_noop!(DistributionModule::claim(Origin::signed(3), 0), Error::<TestRuntime>::AlreadyClaimed);
+		assert_ok!(DistributionModule::claim(Origin::signed(2), 0));
+		assert_eq!(DistributionModule::distribution(0).unwrap().claimed, 300);
+		assert_eq!(PaymentToken::named_reserve_of(1, (1, DIVIDEND)), 700);
+	})
+}
+
+#[test]
+fn reclaim_ok() {
+	build_ext().execute_with(|| {
+		assert_ok!(DistributionModule::create_distribution(Origin::signed(1), 1, 15));
+		assert_ok!(DistributionModule::claim(Origin::signed(2), 0));
+
+		assert_noop!(DistributionModule::reclaim(Origin::signed(1), 0), Error::<TestRuntime>::ClaimPeriodNotOver);
+		System::set_block_number(101);
+		assert_noop!(DistributionModule::claim(Origin::signed(3), 0), Error::<TestRuntime>::ClaimPeriodOver);
+		assert_noop!(DistributionModule::reclaim(Origin::signed(2), 0), Error::<TestRuntime>::NotCreator);
+
+		//the unclaimed shares and the rounding leftover go back with the deposit
+		assert_ok!(DistributionModule::reclaim(Origin::signed(1), 0));
+		assert_eq!(PaymentToken::free_balance(1, 1), 1000000 - 4);
+		assert_eq!(PaymentToken::free_balance(0, 1), 600);
+		assert_eq!(PaymentToken::reserved_balance(1, 1), 0);
+		assert_eq!(PaymentToken::reserved_balance(0, 1), 0);
+		assert_eq!(DistributionModule::distribution(0), None);
+		assert_noop!(DistributionModule::reclaim(Origin::signed(1), 0), Error::<TestRuntime>::DistributionNotExist);
+		assert_noop!(DistributionModule::claim(Origin::signed(2), 0), Error::<TestRuntime>::DistributionNotExist);
+	})
+}
@@ -0,0 +1,223 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+///escrow module to handle
+/// funds of a payer held for a payee until delivery, under an escrow id
+/// release to the payee by the payer, or by anyone presenting the agreed proof
+/// refund to the payer by the payee, or by the payer once the escrow expired
+/// resolution of a dispute by the arbiter
+/// escrowed funds stay in a reserve of the payer tagged with the escrow reserve id
+use codec::{Decode, Encode};
+use frame_support::{decl_error, decl_event, decl_module, decl_storage, dispatch::DispatchResult, ensure, traits::Get};
+use sp_runtime::{
+	traits::{CheckedSub, Hash, Zero},
+	RuntimeDebug,
+};
+use sp_std::prelude::*;
+use system::ensure_signed;
+
+#[cfg(all(feature = "std", test))]
+mod mock;
+
+#[cfg(all(feature = "std", test))]
+mod tests;
+
+pub type EscrowId = u64;
+
+type AssetIdOf<T> = <T as payment_token::Trait>::AssetId;
+type BalanceOf<T> = <T as payment_token::Trait>::Balance;
+
+/// The pallet's configuration trait.
+pub trait Trait: payment_token::Trait {
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+	/// Tag of the payment token reserve escrowed funds are held in.
+	type EscrowReserveId: Get<<Self as payment_token::Trait>::ReserveIdentifier>;
+}
+
+/// What lets funds go to the payee, besides the arbiter's resolution.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum ReleaseCondition<Hash> {
+	/// Only the payer may release the funds.
+	PayerApproval,
+	/// The payer may release the funds, and so may anyone presenting data hashing to this value
+	/// before the escrow expires, e.g. a delivery proof handed over by the payer.
+	HashLock(Hash),
+}
+
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Escrow<AccountId, AssetId, Balance, BlockNumber, Hash> {
+	pub payer: AccountId,
+	pub payee: AccountId,
+	/// Settles disputes, splitting the funds between payer and payee.
+	pub arbiter: AccountId,
+	pub asset_id: AssetId,
+	pub amount: Balance,
+	/// From this block on, the payer may take the funds back.
+	pub expiry: BlockNumber,
+	pub condition: ReleaseCondition<Hash>,
+}
+
+type EscrowOf<T> = Escrow<
+	<T as system::Trait>::AccountId,
+	AssetIdOf<T>,
+	BalanceOf<T>,
+	<T as system::Trait>::BlockNumber,
+	<T as system::Trait>::Hash,
+>;
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Escrow {
+		NextEscrowId get(next_escrow_id): EscrowId;
+		/// Escrows still holding funds. Settled escrows are removed.
+		Escrows get(escrow): map hasher(blake2_256) EscrowId => Option<EscrowOf<T>>;
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event() = default;
+
+		/// Hold `amount` of the sender's free balance for `payee` until released, refunded or resolved.
+		fn create_escrow(
+			origin,
+			asset_id: AssetIdOf<T>,
+			payee: T::AccountId,
+			arbiter: T::AccountId,
+			amount: BalanceOf<T>,
+			expiry: T::BlockNumber,
+			condition: ReleaseCondition<T::Hash>
+		) -> DispatchResult {
+			let payer = ensure_signed(origin)?;
+			ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
+			ensure!(payer != payee, Error::<T>::PayerIsPayee);
+			ensure!(arbiter != payer && arbiter != payee, Error::<T>::ArbiterIsParty);
+			ensure!(expiry > <system::Module<T>>::block_number(), Error::<T>::ExpiryInPast);
+
+			let escrow_id = Self::next_escrow_id();
+			let next_escrow_id = escrow_id.checked_add(1).ok_or(Error::<T>::NoAvailableEscrowId)?;
+			<payment_token::Module<T>>::do_reserve(&asset_id, &payer, &T::EscrowReserveId::get(), amount)?;
+
+			<Escrows<T>>::insert(escrow_id, Escrow {
+				payer: payer.clone(),
+				payee: payee.clone(),
+				arbiter,
+				asset_id,
+				amount,
+				expiry,
+				condition,
+			});
+			NextEscrowId::put(next_escrow_id);
+
+			Self::deposit_event(RawEvent::EscrowCreated(escrow_id, payer, payee, amount));
+			Ok(())
+		}
+
+		/// Pay the escrowed funds out to the payee. Only the payer may do so.
+		fn release(origin, escrow_id: EscrowId) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let escrow = Self::escrow(escrow_id).ok_or(Error::<T>::EscrowNotExist)?;
+			ensure!(sender == escrow.payer, Error::<T>::NotPayer);
+
+			Self::settle(escrow_id, &escrow, escrow.amount)?;
+			Self::deposit_event(RawEvent::EscrowReleased(escrow_id));
+			Ok(())
+		}
+
+		/// Pay the escrowed funds out to the payee by presenting data matching its hash lock.
+		/// Anyone may do so until the escrow expires.
+		fn release_with_proof(origin, escrow_id: EscrowId, proof: Vec<u8>) -> DispatchResult {
+			ensure_signed(origin)?;
+			let escrow = Self::escrow(escrow_id).ok_or(Error::<T>::EscrowNotExist)?;
+			ensure!(<system::Module<T>>::block_number() < escrow.expiry, Error::<T>::EscrowExpired);
+			match escrow.condition {
+				ReleaseCondition::HashLock(hash) => ensure!(T::Hashing::hash(&proof) == hash, Error::<T>::InvalidProof),
+				ReleaseCondition::PayerApproval => return Err(Error::<T>::InvalidProof.into()),
+			}
+
+			Self::settle(escrow_id, &escrow, escrow.amount)?;
+			Self::deposit_event(RawEvent::EscrowReleased(escrow_id));
+			Ok(())
+		}
+
+		/// Give the escrowed funds back to the payer. The payee may do so at any time, the payer
+		/// only once the escrow expired.
+		fn refund(origin, escrow_id: EscrowId) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let escrow = Self::escrow(escrow_id).ok_or(Error::<T>::EscrowNotExist)?;
+			let expired = <system::Module<T>>::block_number() >= escrow.expiry;
+			ensure!(sender == escrow.payee || (sender == escrow.payer && expired), Error::<T>::CannotRefund);
+
+			Self::settle(escrow_id, &escrow, Zero::zero())?;
+			Self::deposit_event(RawEvent::EscrowRefunded(escrow_id));
+			Ok(())
+		}
+
+		/// Settle a dispute: `to_payee` goes to the payee and the rest back to the payer.
+		fn resolve(origin, escrow_id: EscrowId, to_payee: BalanceOf<T>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let escrow = Self::escrow(escrow_id).ok_or(Error::<T>::EscrowNotExist)?;
+			ensure!(sender == escrow.arbiter, Error::<T>::NotArbiter);
+			let to_payer = escrow.amount.checked_sub(&to_payee).ok_or(Error::<T>::AmountTooHigh)?;
+
+			Self::settle(escrow_id, &escrow, to_payee)?;
+			Self::deposit_event(RawEvent::EscrowResolved(escrow_id, to_payee, to_payer));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Pay `to_payee` out of the escrow to the payee, hand the rest back to the payer and remove the escrow.
+	/// If the funds can't be moved, e.g. because an account is frozen, nothing is and the escrow is kept.
+	fn settle(escrow_id: EscrowId, escrow: &EscrowOf<T>, to_payee: BalanceOf<T>) -> DispatchResult {
+		let to_payer = escrow.amount - to_payee;
+		<payment_token::Module<T>>::do_settle_reserved(
+			&escrow.asset_id,
+			&escrow.payer,
+			&T::EscrowReserveId::get(),
+			&escrow.payee,
+			to_payee,
+			to_payer,
+		)?;
+
+		<Escrows<T>>::remove(escrow_id);
+		Ok(())
+	}
+}
+
+decl_event!(
+	pub enum Event<T>
+	where
+		AccountId = <T as system::Trait>::AccountId,
+		Balance = BalanceOf<T>,
+	{
+		/// Funds were put in escrow (escrow id, payer, payee, amount).
+		EscrowCreated(EscrowId, AccountId, AccountId, Balance),
+		/// The escrowed funds went to the payee.
+		EscrowReleased(EscrowId),
+		/// The escrowed funds went back to the payer.
+		EscrowRefunded(EscrowId),
+		/// The arbiter split the escrowed funds (escrow id, paid to payee, refunded to payer).
+		EscrowResolved(EscrowId, Balance, Balance),
+	}
+);
+
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		ZeroAmount,
+		PayerIsPayee,
+		/// The arbiter must be neither the payer nor the payee
+		ArbiterIsParty,
+		ExpiryInPast,
+		NoAvailableEscrowId,
+		EscrowNotExist,
+		EscrowExpired,
+		NotPayer,
+		NotArbiter,
+		/// Only the payee, or the payer after expiry, may refund
+		CannotRefund,
+		/// The proof doesn't match the hash lock of the escrow
+		InvalidProof,
+		/// The arbiter can't pay out more than is escrowed
+		AmountTooHigh,
+	}
+}
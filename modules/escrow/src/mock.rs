@@ -0,0 +1,83 @@
+//mocks for escrow
+#[cfg(test)]
+use super::*;
+
+use frame_support::{impl_outer_origin, parameter_types, weights::Weight};
+use sp_core::H256;
+use sp_runtime::{
+	testing::{Header, TestSignature, UintAuthorityId},
+	traits::{BlakeTwo256, ConvertInto, IdentityLookup},
+	Perbill,
+};
+
+impl_outer_origin! {
+	pub enum Origin for TestRuntime {}
+}
+
+#[derive(Clone, Eq, PartialEq)]
+pub struct TestRuntime;
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+	pub const EscrowReserveId: [u8; 8] = *b"escrow  ";
+	pub const StreamReserveId: [u8; 8] = *b"stream  ";
+}
+
+impl system::Trait for TestRuntime {
+	type Origin = Origin;
+	type Call = ();
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = ();
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type ModuleToIndex = ();
+}
+
+impl payment_token::Trait for TestRuntime {
+	type Event = ();
+	type Balance = u64;
+	type AssetId = u32;
+	type BlockNumberToBalance = ConvertInto;
+	type ReserveIdentifier = [u8; 8];
+	type DustHandler = ();
+	type StreamReserveId = StreamReserveId;
+	type Signature = TestSignature;
+	type Signer = UintAuthorityId;
+}
+
+impl Trait for TestRuntime {
+	type Event = ();
+	type EscrowReserveId = EscrowReserveId;
+}
+
+pub type Escrow = Module<TestRuntime>;
+pub type PaymentToken = payment_token::Module<TestRuntime>;
+pub type System = system::Module<TestRuntime>;
+
+// Account 1 owns the RKX supply and hands 1000 to the payer, account 2.
+pub fn build_ext() -> sp_io::TestExternalities {
+	let mut t = system::GenesisConfig::default().build_storage::<TestRuntime>().unwrap();
+	payment_token::GenesisConfig::<TestRuntime> {
+		assets: vec![(0, 1, 21000000)],
+		vesting: vec![],
+		minimum_balances: vec![],
+		metadata: vec![],
+	}.assimilate_storage(&mut t).unwrap();
+	let mut ext: sp_io::TestExternalities = t.into();
+	ext.execute_with(|| {
+		assert!(PaymentToken::transfer_from(Origin::signed(1), 0, 2, 1000).is_ok());
+		System::set_block_number(1);
+	});
+	ext
+}
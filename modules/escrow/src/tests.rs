@@ -0,0 +1,127 @@
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::{build_ext, Escrow, Origin, PaymentToken, System, TestRuntime};
+use sp_core::H256;
+use sp_runtime::traits::BlakeTwo256;
+
+const PAYER: u64 = 2;
+const PAYEE: u64 = 3;
+const ARBITER: u64 = 4;
+const ESCROW: [u8; 8] = *b"escrow  ";
+
+fn create(amount: u64, condition: ReleaseCondition<H256>) -> DispatchResult {
+	Escrow::create_escrow(Origin::signed(PAYER), 0, PAYEE, ARBITER, amount, 10, condition)
+}
+
+#[test]
+fn create_escrow_ok() {
+	build_ext().execute_with(|| {
+		assert_noop!(create(0, ReleaseCondition::PayerApproval), Error::<TestRuntime>::ZeroAmount);
+		assert_noop!(
+			Escrow::create_escrow(Origin::signed(PAYER), 0, PAYEE, PAYEE, 100, 10, ReleaseCondition::PayerApproval),
+			Error::<TestRuntime>::ArbiterIsParty
+		);
+		assert_noop!(
+			Escrow::create_escrow(Origin::signed(PAYER), 0, PAYEE, PAYER, 100, 10, ReleaseCondition::PayerApproval),
+			Error::<TestRuntime>::ArbiterIsParty
+		);
+		assert_noop!(
+			Escrow::create_escrow(Origin::signed(PAYER), 0, PAYEE, ARBITER, 100, 1, ReleaseCondition::PayerApproval),
+			Error::<TestRuntime>::ExpiryInPast
+		);
+		assert_noop!(create(1001, ReleaseCondition::PayerApproval), payment_token::Error::<TestRuntime>::AmountTooLow);
+
+		assert_ok!(create(600, ReleaseCondition::PayerApproval));
+		assert_eq!(Escrow::next_escrow_id(), 1);
+		assert_eq!(Escrow::escrow(0).unwrap().amount, 600);
+		//funds are held in the escrow reserve of the payer
+		assert_eq!(PaymentToken::free_balance(0, PAYER), 400);
+		assert_eq!(PaymentToken::named_reserve_of(0, (PAYER, ESCROW)), 600);
+	})
+}
+
+#[test]
+fn release_ok() {
+	build_ext().execute_with(|| {
+		assert_ok!(create(600, ReleaseCondition::PayerApproval));
+		assert_noop!(Escrow::release(Origin::signed(PAYEE), 0), Error::<TestRuntime>::NotPayer);
+		assert_ok!(Escrow::release(Origin::signed(PAYER), 0));
+
+		assert_eq!(PaymentToken::free_balance(0, PAYEE), 600);
+		assert_eq!(PaymentToken::reserved_balance(0, PAYER), 0);
+		assert_eq!(Escrow::escrow(0), None);
+		assert_noop!(Escrow::release(Origin::signed(PAYER), 0), Error::<TestRuntime>::EscrowNotExist);
+	})
+}
+
+#[test]
+fn release_with_proof_ok() {
+	build_ext().execute_with(|| {
+		let proof = b"delivered".to_vec();
+		assert_ok!(create(600, ReleaseCondition::HashLock(BlakeTwo256::hash(&proof))));
+		assert_ok!(create(100, ReleaseCondition::PayerApproval));
+
+		assert_noop!(Escrow::release_with_proof(Origin::signed(PAYEE), 0, b"wrong".to_vec()), Error::<TestRuntime>::InvalidProof);
+		assert_noop!(Escrow::release_with_proof(Origin::signed(PAYEE), 1, proof.clone()), Error::<TestRuntime>::InvalidProof);
+
+		System::set_block_number(10);
+		assert_noop!(Escrow::release_with_proof(Origin::signed(PAYEE), 0, proof.clone()), Error::<TestRuntime>::EscrowExpired);
+
+		System::set_block_number(9);
+		assert_ok!(Escrow::release_with_proof(Origin::signed(PAYEE), 0, proof));
+		assert_eq!(PaymentToken::free_balance(0, PAYEE), 600);
+		assert_eq!(PaymentToken::reserved_balance(0, PAYER), 100);
+	})
+}
+
+#[test]
+fn refund_ok() {
+	build_ext().execute_with(|| {
+		assert_ok!(create(600, ReleaseCondition::PayerApproval));
+		assert_ok!(create(100, ReleaseCondition::PayerApproval));
+
+		//the payer has to wait for the expiry, the payee doesn't
+		assert_noop!(Escrow::refund(Origin::signed(PAYER), 0), Error::<TestRuntime>::CannotRefund);
+		assert_noop!(Escrow::refund(Origin::signed(ARBITER), 0), Error::<TestRuntime>::CannotRefund);
+		assert_ok!(Escrow::refund(Origin::signed(PAYEE), 0));
+		assert_eq!(PaymentToken::free_balance(0, PAYER), 900);
+
+		System::set_block_number(10);
+		assert_ok!(Escrow::refund(Origin::signed(PAYER), 1));
+		assert_eq!(PaymentToken::free_balance(0, PAYER), 1000);
+		assert_eq!(PaymentToken::reserved_balance(0, PAYER), 0);
+	})
+}
+
+#[test]
+fn resolve_ok() {
+	build_ext().execute_with(|| {
+		assert_ok!(create(600, ReleaseCondition::PayerApproval));
+
+		assert_noop!(Escrow::resolve(Origin::signed(PAYER), 0, 600), Error::<TestRuntime>::NotArbiter);
+		assert_noop!(Escrow::resolve(Origin::signed(ARBITER), 0, 601), Error::<TestRuntime>::AmountTooHigh);
+		assert_ok!(Escrow::resolve(Origin::signed(ARBITER), 0, 200));
+
+		assert_eq!(PaymentToken::free_balance(0, PAYEE), 200);
+		assert_eq!(PaymentToken::free_balance(0, PAYER), 800);
+		assert_eq!(PaymentToken::reserved_balance(0, PAYER), 0);
+		assert_eq!(Escrow::escrow(0), None);
+	})
+}
+
+#[test]
+fn settle_frozen_payer_moves_nothing() {
+	build_ext().execute_with(|| {
+		assert_ok!(create(600, ReleaseCondition::PayerApproval));
+		assert_ok!(PaymentToken::freeze_account(Origin::signed(1), 0, PAYER));
+
+		//the payee isn't paid while the refund of the payer would fail, and the escrow stays
+		assert_noop!(Escrow::resolve(Origin::signed(ARBITER), 0, 200), payment_token::Error::<TestRuntime>::AccountFrozen);
+		assert_eq!(PaymentToken::free_balance(0, PAYEE), 0);
+
+		assert_ok!(PaymentToken::thaw_account(Origin::signed(1), 0, PAYER));
+		assert_ok!(Escrow::resolve(Origin::signed(ARBITER), 0, 200));
+		assert_eq!(PaymentToken::free_balance(0, PAYEE), 200);
+		assert_eq!(PaymentToken::free_balance(0, PAYER), 800);
+	})
+}
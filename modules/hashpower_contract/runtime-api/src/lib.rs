@@ -0,0 +1,16 @@
+//! Runtime API definition for the hashpower contract module.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+
+sp_api::decl_runtime_apis! {
+	/// Queries over the hash power sold by service providers.
+	pub trait HashPowerContractApi<AccountId, BlockNumber> where
+		AccountId: Codec,
+		BlockNumber: Codec,
+	{
+		/// Hash power `provider` can still sell over every block from `start` until `end`.
+		fn free_capacity(provider: AccountId, start: BlockNumber, end: BlockNumber) -> u64;
+	}
+}
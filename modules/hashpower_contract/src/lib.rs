@@ -1,19 +1,74 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use codec::{Decode, Encode};
-use frame_support::traits::Randomness;
+use frame_support::traits::{Currency, ExistenceRequirement, Get, Randomness, WithdrawReason};
 /// rockx pallet hashpower contract
 /// define contract structure as HashPowerContract
 /// method for
-use frame_support::{decl_event, decl_module, decl_storage, dispatch::DispatchResult, ensure};
+/// contract creation and transfer
+/// offers of providers selling hash power at a price per TH/s per day, bought in RKX
+/// marketplace where owners sell their contracts on for RKX, with an optional fee to the treasury
+/// hash power committed by each provider over time, contracts can't oversell a provider's capacity
+/// contract status, moved on automatically when a contract starts and ends, or by disputes and termination
+use frame_support::{decl_error, decl_event, decl_module, decl_storage, dispatch::{DispatchError, DispatchResult}, ensure};
 //use sp_runtime::traits::{CheckedAdd, CheckedDiv, CheckedMul, Hash};
-use sp_runtime::traits::Hash;
-use sp_runtime::RuntimeDebug;
-use system::ensure_signed;
+use sp_runtime::traits::{CheckedAdd, CheckedMul, Hash, SaturatedConversion, Zero};
+use sp_runtime::{Permill, RuntimeDebug};
+use sp_std::prelude::*;
+use system::{ensure_root, ensure_signed};
 
 pub trait Trait: system::Trait {
 	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
 	type Randomness: Randomness<<Self as system::Trait>::Hash>;
+	/// Shortest term of a contract, in blocks.
+	type MinDuration: Get<Self::BlockNumber>;
+	/// Longest term of a contract, in blocks.
+	type MaxDuration: Get<Self::BlockNumber>;
+	/// Least hash power a contract may sell.
+	type MinHashPower: Get<ValueHashPower>;
+	/// Most hash power a contract may sell.
+	type MaxHashPower: Get<ValueHashPower>;
+	/// The service providers contracts may be created for.
+	type ServiceProviders: ServiceProviders<Self::AccountId>;
+	/// The currency offers are paid in, RKX.
+	type Currency: Currency<Self::AccountId>;
+	/// Number of blocks in a day, the unit offers are priced and termed in.
+	type BlocksPerDay: Get<Self::BlockNumber>;
+	/// Most contracts that may start, or end, at the same block, bounding the work of `on_initialize`.
+	type MaxScheduledPerBlock: Get<u32>;
+	/// Who the currency refuses to pay, checked before any payment of a sale is made.
+	type Payees: Payees<Self::AccountId>;
+}
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+
+/// Registry of the service providers selling hash power.
+pub trait ServiceProviders<AccountId> {
+	/// Whether `who` is a registered provider currently allowed to sell hash power.
+	fn is_active(who: &AccountId) -> bool;
+	/// Total hash power `who` declares to operate.
+	fn capacity(who: &AccountId) -> ValueHashPower;
+}
+
+/// Restrictions of the currency on who may be paid, beyond the minimum balance.
+pub trait Payees<AccountId> {
+	/// Whether `who` can be paid right now, e.g. isn't frozen.
+	fn can_receive(who: &AccountId) -> bool;
+}
+
+impl<AccountId> Payees<AccountId> for () {
+	fn can_receive(_: &AccountId) -> bool {
+		true
+	}
+}
+
+/// Hash power of a contract a provider is committed to deliver from `start` until `end`.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+pub struct Commitment<Hash, BlockNumber> {
+	pub contract_id: Hash,
+	pub hash_power: ValueHashPower,
+	pub start: BlockNumber,
+	pub end: BlockNumber,
 }
 
 #[cfg(all(feature = "std", test))]
@@ -24,6 +79,41 @@ mod mock;
 
 pub type ValueHashPower = u64;
 
+pub type OfferId = u64;
+
+/// Hash power a provider sells, in contracts of `term` days starting whenever the buyer chooses.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+pub struct Offer<AccountId, Balance> {
+	pub provider: AccountId,
+	/// Price of one TH/s for one day.
+	pub price_per_day: Balance,
+	/// Hash power left for sale.
+	pub quantity: ValueHashPower,
+	/// Term of the contracts sold, in days.
+	pub term: u32,
+}
+
+/// Where a contract is in its life.
+#[derive(Encode, Decode, Copy, Clone, Eq, PartialEq, RuntimeDebug)]
+pub enum ContractStatus {
+	/// Created, waiting for its start date.
+	Pending,
+	/// Between its start and end dates.
+	Active,
+	/// Past its end date.
+	Expired,
+	/// Ended early by governance.
+	Terminated,
+	/// The owner disputes the delivery of the hash power, waiting for governance to settle it.
+	Disputed,
+}
+
+impl Default for ContractStatus {
+	fn default() -> Self {
+		ContractStatus::Pending
+	}
+}
+
 //#[cfg_attr(feature = "std", derive())]
 #[derive(Encode, Decode, Eq, PartialEq, RuntimeDebug, Default)]
 pub struct HashPowerContract<Hash, BlockNumber, AccountId> {
@@ -32,6 +122,7 @@ pub struct HashPowerContract<Hash, BlockNumber, AccountId> {
 	start_date: BlockNumber,
 	end_date: BlockNumber,
 	service_provider: AccountId,
+	status: ContractStatus,
 }
 
 decl_storage! {
@@ -47,6 +138,24 @@ decl_storage! {
 		OwnedContractsCount get(owned_contract_count): map T::AccountId => u64;
 		OwnedContractsIndex get(owned_index_of): map T::Hash => u64;
 
+		/// Contracts to activate at the start of a block.
+		ContractStarts get(contracts_starting): map T::BlockNumber => Vec<T::Hash>;
+		/// Contracts to expire at the start of a block.
+		ContractEnds get(contracts_ending): map T::BlockNumber => Vec<T::Hash>;
+
+		/// Commitments of a provider to contracts not yet ended.
+		Commitments get(commitments_of): map T::AccountId => Vec<Commitment<T::Hash, T::BlockNumber>>;
+
+		NextOfferId get(next_offer_id): OfferId;
+		Offers get(offer): map OfferId => Option<Offer<T::AccountId, BalanceOf<T>>>;
+
+		/// Price of contracts listed for sale by their owner.
+		Listings get(listing): map T::Hash => Option<BalanceOf<T>>;
+		/// Share of the price of each contract sold on the marketplace paid to the treasury.
+		MarketFee get(market_fee): Permill;
+		/// The account marketplace fees are paid to. No fee is taken while this is unset.
+		Treasury get(treasury): Option<T::AccountId>;
+
 		Nonce get(nonce): u64;
 	}
 }
@@ -55,6 +164,15 @@ decl_module! {
 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
 		fn deposit_event() = default;
 
+		const MinDuration: T::BlockNumber = T::MinDuration::get();
+		const MaxDuration: T::BlockNumber = T::MaxDuration::get();
+		const MinHashPower: ValueHashPower = T::MinHashPower::get();
+		const MaxHashPower: ValueHashPower = T::MaxHashPower::get();
+		const MaxScheduledPerBlock: u32 = T::MaxScheduledPerBlock::get();
+
+		/// Mint a contract for the sender's own hash power, e.g. to hand it over with `transfer_contract`
+		/// after a sale off chain. Only `service_provider` itself may do so, and it must be active and have
+		/// the hash power free over the whole period.
 		pub fn create_contract(
 			origin,
 			hash_power: ValueHashPower,
@@ -63,34 +181,233 @@ decl_module! {
 			service_provider: T::AccountId
 		) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
+			ensure!(sender == service_provider, Error::<T>::NotServiceProvider);
 
-			//@@XM TODO: add some sanity check of contract parameters? not now
-			let nonce = Self::nonce();
-			//let random_hash = (&sender,nonce).using_encoded(<T as system::Trait>::Hashing::hash);
-			let random_hash = Self::random_number(nonce);
-
-			let new_contract  = HashPowerContract {
-				id: random_hash,
-				hash_power: hash_power,
-				start_date: start_date,
-				end_date: end_date,
-				service_provider: service_provider,
-			};
-			//mint a contract
-			Self::mint_contract(&sender, &random_hash, &new_contract)?;
+			Self::ensure_can_sell(&service_provider, hash_power, start_date, end_date)?;
+			Self::ensure_can_mint(&sender)?;
+			Self::create(&sender, &service_provider, hash_power, start_date, end_date)?;
+			Ok(())
+		}
 
-			<Nonce>::mutate(|nonce| *nonce += 1);
+		/// Offer `quantity` TH/s for sale at `price_per_day` per TH/s, in contracts of `term` days.
+		pub fn create_offer(origin, price_per_day: BalanceOf<T>, quantity: ValueHashPower, term: u32) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(T::ServiceProviders::is_active(&sender), Error::<T>::ProviderNotActive);
+			ensure!(!price_per_day.is_zero(), Error::<T>::ZeroPrice);
+			ensure!(!quantity.is_zero(), Error::<T>::ZeroHashPower);
+			let duration = Self::term_duration(term)?;
+			ensure!(duration >= T::MinDuration::get(), Error::<T>::DurationTooShort);
+			ensure!(duration <= T::MaxDuration::get(), Error::<T>::DurationTooLong);
+
+			let offer_id = Self::next_offer_id();
+			let next_offer_id = offer_id.checked_add(1).ok_or(Error::<T>::OverFlowHappens)?;
+			<Offers<T>>::insert(offer_id, Offer {
+				provider: sender.clone(),
+				price_per_day,
+				quantity,
+				term,
+			});
+			NextOfferId::put(next_offer_id);
+
+			Self::deposit_event(RawEvent::OfferCreated(offer_id, sender, price_per_day, quantity, term));
+			Ok(())
+		}
+
+		pub fn cancel_offer(origin, offer_id: OfferId) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let offer = Self::offer(offer_id).ok_or(Error::<T>::OfferNotExist)?;
+			ensure!(offer.provider == sender, Error::<T>::NotOfferProvider);
+
+			<Offers<T>>::remove(offer_id);
+			Self::deposit_event(RawEvent::OfferCancelled(offer_id));
+			Ok(())
+		}
+
+		/// Buy `hash_power` out of an offer, starting at `start_date`. The price is paid to the provider
+		/// and the contract minted to the sender in one go, everything is checked before paying.
+		pub fn purchase_offer(
+			origin,
+			offer_id: OfferId,
+			hash_power: ValueHashPower,
+			start_date: T::BlockNumber
+		) -> DispatchResult {
+			let buyer = ensure_signed(origin)?;
+			let mut offer = Self::offer(offer_id).ok_or(Error::<T>::OfferNotExist)?;
+			ensure!(buyer != offer.provider, Error::<T>::BuyerIsProvider);
+			ensure!(hash_power <= offer.quantity, Error::<T>::ExceedsOffer);
+
+			let end_date = start_date.checked_add(&Self::term_duration(offer.term)?).ok_or(Error::<T>::OverFlowHappens)?;
+			Self::ensure_can_sell(&offer.provider, hash_power, start_date, end_date)?;
+			let price = Self::offer_price(&offer, hash_power).ok_or(Error::<T>::OverFlowHappens)?;
+			ensure!(T::Currency::free_balance(&buyer) >= price, Error::<T>::InsufficientBalance);
+			Self::ensure_can_mint(&buyer)?;
+
+			T::Currency::transfer(&buyer, &offer.provider, price, ExistenceRequirement::KeepAlive)?;
+			let contract_id = Self::create(&buyer, &offer.provider, hash_power, start_date, end_date)?;
+
+			offer.quantity -= hash_power;
+			if offer.quantity.is_zero() {
+				<Offers<T>>::remove(offer_id);
+			} else {
+				<Offers<T>>::insert(offer_id, offer);
+			}
+			Self::deposit_event(RawEvent::OfferPurchased(offer_id, buyer, contract_id, price));
 			Ok(())
 		}
 
 		pub fn transfer_contract(origin, to: T::AccountId, contract_id: T::Hash) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
 			//make sure sender is the owner
-			ensure!(Self::owner_of(contract_id) == Some(sender.clone()), "can only transfer your own contract");
+			ensure!(Self::owner_of(contract_id) == Some(sender.clone()), Error::<T>::NotContractOwner);
 
 			Self::transfer_from(&sender, &to, &contract_id)?;
 			Ok(())
 		}
+
+		/// Put a contract of the sender up for sale at `price`.
+		pub fn list_for_sale(origin, contract_id: T::Hash, price: BalanceOf<T>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(Self::owner_of(contract_id) == Some(sender.clone()), Error::<T>::NotContractOwner);
+			ensure!(Self::is_tradable(&contract_id), Error::<T>::InvalidStatus);
+			ensure!(!price.is_zero(), Error::<T>::ZeroPrice);
+
+			<Listings<T>>::insert(contract_id, price);
+			Self::deposit_event(RawEvent::ContractListed(contract_id, sender, price));
+			Ok(())
+		}
+
+		pub fn cancel_listing(origin, contract_id: T::Hash) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(Self::owner_of(contract_id) == Some(sender), Error::<T>::NotContractOwner);
+			ensure!(<Listings<T>>::exists(contract_id), Error::<T>::NotListed);
+
+			<Listings<T>>::remove(contract_id);
+			Self::deposit_event(RawEvent::ListingCancelled(contract_id));
+			Ok(())
+		}
+
+		pub fn update_price(origin, contract_id: T::Hash, price: BalanceOf<T>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(Self::owner_of(contract_id) == Some(sender.clone()), Error::<T>::NotContractOwner);
+			ensure!(<Listings<T>>::exists(contract_id), Error::<T>::NotListed);
+			ensure!(!price.is_zero(), Error::<T>::ZeroPrice);
+
+			<Listings<T>>::insert(contract_id, price);
+			Self::deposit_event(RawEvent::ContractListed(contract_id, sender, price));
+			Ok(())
+		}
+
+		/// Buy a listed contract, paying its price to the owner less the marketplace fee. `max_price`
+		/// guards against the price being raised before the purchase goes through. Everything is checked
+		/// before paying, and the contract handed over last.
+		pub fn buy(origin, contract_id: T::Hash, max_price: BalanceOf<T>) -> DispatchResult {
+			let buyer = ensure_signed(origin)?;
+			let price = Self::listing(contract_id).ok_or(Error::<T>::NotListed)?;
+			let seller = Self::owner_of(contract_id).ok_or(Error::<T>::ContractNotExist)?;
+			ensure!(buyer != seller, Error::<T>::TransferToSelf);
+			ensure!(price <= max_price, Error::<T>::PriceTooHigh);
+			ensure!(Self::is_tradable(&contract_id), Error::<T>::InvalidStatus);
+
+			let treasury = Self::treasury();
+			let fee = match treasury {
+				Some(_) => Self::market_fee() * price,
+				None => Zero::zero(),
+			};
+			let proceeds = price - fee;
+			//the price is paid in two transfers, everything either of them checks is checked first so that
+			//the second can't fail once the first went through
+			let free = T::Currency::free_balance(&buyer);
+			ensure!(free >= price, Error::<T>::InsufficientBalance);
+			ensure!(free - price >= T::Currency::minimum_balance(), Error::<T>::InsufficientBalance);
+			T::Currency::ensure_can_withdraw(&buyer, price, WithdrawReason::Transfer.into(), free - price)?;
+			Self::ensure_can_receive(&seller, proceeds)?;
+			if let Some(treasury) = &treasury {
+				Self::ensure_can_receive(treasury, fee)?;
+			}
+			ensure!(Self::owned_contract_count(&buyer).checked_add(1).is_some(), Error::<T>::OverFlowHappens);
+
+			if !proceeds.is_zero() {
+				T::Currency::transfer(&buyer, &seller, proceeds, ExistenceRequirement::KeepAlive)?;
+			}
+			if let Some(treasury) = &treasury {
+				if !fee.is_zero() {
+					T::Currency::transfer(&buyer, treasury, fee, ExistenceRequirement::KeepAlive)?;
+				}
+			}
+			Self::transfer_from(&seller, &buyer, &contract_id)?;
+
+			Self::deposit_event(RawEvent::ContractSold(contract_id, seller, buyer, price, fee));
+			Ok(())
+		}
+
+		/// Set the marketplace fee and the treasury it is paid to, or stop taking it with `None`.
+		pub fn set_market_fee(origin, fee: Permill, treasury: Option<T::AccountId>) -> DispatchResult {
+			ensure_root(origin)?;
+
+			MarketFee::put(fee);
+			match &treasury {
+				Some(treasury) => <Treasury<T>>::put(treasury),
+				None => <Treasury<T>>::kill(),
+			}
+			Self::deposit_event(RawEvent::MarketFeeSet(fee, treasury));
+			Ok(())
+		}
+
+		/// Dispute the delivery of an active contract. Only the owner may do so.
+		pub fn dispute_contract(origin, contract_id: T::Hash) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(Self::owner_of(contract_id) == Some(sender), Error::<T>::NotContractOwner);
+			ensure!(Self::contract(contract_id).status == ContractStatus::Active, Error::<T>::InvalidStatus);
+
+			Self::set_status(&contract_id, ContractStatus::Disputed);
+			Ok(())
+		}
+
+		/// Settle a dispute, either terminating the contract or putting it back in force.
+		pub fn resolve_dispute(origin, contract_id: T::Hash, terminate: bool) -> DispatchResult {
+			ensure_root(origin)?;
+			let contract = Self::contract(contract_id);
+			ensure!(contract.status == ContractStatus::Disputed, Error::<T>::InvalidStatus);
+
+			if terminate {
+				Self::terminate(&contract_id);
+			} else if <system::Module<T>>::block_number() < contract.end_date {
+				Self::set_status(&contract_id, ContractStatus::Active);
+			} else {
+				//the end date passed during the dispute
+				Self::set_status(&contract_id, ContractStatus::Expired);
+			}
+			Ok(())
+		}
+
+		/// End a contract before its end date.
+		pub fn terminate_contract(origin, contract_id: T::Hash) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(<Contracts<T>>::exists(contract_id), Error::<T>::ContractNotExist);
+			match Self::contract(contract_id).status {
+				ContractStatus::Pending | ContractStatus::Active | ContractStatus::Disputed => (),
+				_ => return Err(Error::<T>::InvalidStatus.into()),
+			}
+
+			Self::terminate(&contract_id);
+			Ok(())
+		}
+
+		/// Move on the contracts starting and ending at this block. This version of the hook can't report
+		/// its weight, instead the work is bounded by `MaxScheduledPerBlock` contracts of each kind.
+		fn on_initialize(now: T::BlockNumber) {
+			for contract_id in <ContractStarts<T>>::take(now) {
+				if Self::contract(contract_id).status == ContractStatus::Pending {
+					Self::set_status(&contract_id, ContractStatus::Active);
+				}
+			}
+			//disputed contracts are left for governance to settle
+			for contract_id in <ContractEnds<T>>::take(now) {
+				if Self::contract(contract_id).status == ContractStatus::Active {
+					Self::set_status(&contract_id, ContractStatus::Expired);
+				}
+			}
+		}
 	}
 }
 
@@ -99,13 +416,91 @@ decl_event!(
 	where
 		AccountId = <T as system::Trait>::AccountId,
 		Hash = <T as system::Trait>::Hash,
+		Balance = BalanceOf<T>,
 	{
 		ContractCreated(AccountId, Hash),
 		ContractTransfered(AccountId, AccountId, Hash),
+		/// A contract moved to a new status.
+		ContractStatusChanged(Hash, ContractStatus),
+		/// A provider offered hash power (offer id, provider, price per TH/s per day, quantity, term in days).
+		OfferCreated(OfferId, AccountId, Balance, ValueHashPower, u32),
+		OfferCancelled(OfferId),
+		/// Hash power was bought out of an offer (offer id, buyer, contract id, price paid).
+		OfferPurchased(OfferId, AccountId, Hash, Balance),
+		/// A contract was listed for sale, or its price updated (contract id, owner, price).
+		ContractListed(Hash, AccountId, Balance),
+		ListingCancelled(Hash),
+		/// A listed contract was sold (contract id, seller, buyer, price, fee paid to the treasury).
+		ContractSold(Hash, AccountId, AccountId, Balance, Balance),
+		MarketFeeSet(Permill, Option<AccountId>),
 	}
 );
 
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// A contract must sell some hash power
+		ZeroHashPower,
+		HashPowerTooLow,
+		HashPowerTooHigh,
+		/// The end date must come after the start date
+		InvalidPeriod,
+		StartInPast,
+		DurationTooShort,
+		DurationTooLong,
+		/// Too many contracts already start or end at that block
+		ScheduleFull,
+		/// The service provider isn't registered, or not approved
+		ProviderNotActive,
+		/// Only the service provider may mint contracts for its hash power
+		NotServiceProvider,
+		/// The provider hasn't got that much hash power left over the contract period
+		InsufficientCapacity,
+		OfferNotExist,
+		NotOfferProvider,
+		BuyerIsProvider,
+		/// The offer hasn't got that much hash power left
+		ExceedsOffer,
+		/// The contract isn't listed for sale
+		NotListed,
+		ZeroPrice,
+		/// The buyer can't pay the price
+		InsufficientBalance,
+		/// A payment would open an account below the minimum balance
+		BelowMinimumBalance,
+		/// The seller or the treasury can't be paid right now, e.g. its account is frozen
+		CannotReceive,
+		/// The listing price is above the most the buyer agreed to pay
+		PriceTooHigh,
+		ContractAlreadyExists,
+		ContractNotExist,
+		NotContractOwner,
+		TransferToSelf,
+		/// The contract isn't in a status allowing this
+		InvalidStatus,
+		OverFlowHappens,
+		UnderFlowHappens,
+	}
+}
+
 impl<T: Trait> Module<T> {
+	/// Check the terms of a new contract against the configured bounds.
+	fn ensure_valid_terms(hash_power: ValueHashPower, start_date: T::BlockNumber, end_date: T::BlockNumber) -> DispatchResult {
+		ensure!(!hash_power.is_zero(), Error::<T>::ZeroHashPower);
+		ensure!(hash_power >= T::MinHashPower::get(), Error::<T>::HashPowerTooLow);
+		ensure!(hash_power <= T::MaxHashPower::get(), Error::<T>::HashPowerTooHigh);
+		ensure!(end_date > start_date, Error::<T>::InvalidPeriod);
+		ensure!(start_date >= <system::Module<T>>::block_number(), Error::<T>::StartInPast);
+
+		let duration = end_date - start_date;
+		ensure!(duration >= T::MinDuration::get(), Error::<T>::DurationTooShort);
+		ensure!(duration <= T::MaxDuration::get(), Error::<T>::DurationTooLong);
+
+		let max_scheduled = T::MaxScheduledPerBlock::get() as usize;
+		ensure!(Self::contracts_starting(start_date).len() < max_scheduled, Error::<T>::ScheduleFull);
+		ensure!(Self::contracts_ending(end_date).len() < max_scheduled, Error::<T>::ScheduleFull);
+		Ok(())
+	}
+
 	fn random_number(nonce: u64) -> T::Hash {
 		let payload = (
 			T::Randomness::random(b"funny_random"),
@@ -122,17 +517,23 @@ impl<T: Trait> Module<T> {
 		contract: &HashPowerContract<T::Hash, T::BlockNumber, T::AccountId>,
 	) -> DispatchResult {
 		//check whether this contract exist or not
-		ensure!(!<ContractOwner<T>>::exists(contract_id), "contract already exits");
+		ensure!(!<ContractOwner<T>>::exists(contract_id), Error::<T>::ContractAlreadyExists);
 
 		//add contract and contract owner
 		<Contracts<T>>::insert(contract_id, contract);
 		<ContractOwner<T>>::insert(contract_id, owner);
 
+		//schedule status changes, a contract starting in the current block is active right away
+		if contract.start_date > <system::Module<T>>::block_number() {
+			<ContractStarts<T>>::mutate(contract.start_date, |ids| ids.push(*contract_id));
+		}
+		<ContractEnds<T>>::mutate(contract.end_date, |ids| ids.push(*contract_id));
+
 		//update all contracts array
 		let old_all_count = Self::contracts_count();
 		let new_all_count = old_all_count
 			.checked_add(1)
-			.ok_or("overflow happens when contracts counts increase")?;
+			.ok_or(Error::<T>::OverFlowHappens)?;
 		<AllContractsArray<T>>::insert(old_all_count, contract_id);
 		<AllContractsCount>::put(new_all_count);
 		<AllContractsIndex<T>>::insert(contract_id, old_all_count);
@@ -141,32 +542,36 @@ impl<T: Trait> Module<T> {
 		let old_owned_count = Self::owned_contract_count(owner);
 		let new_owned_count = old_owned_count
 			.checked_add(1)
-			.ok_or("overflow happends when owned contract counts increate")?;
+			.ok_or(Error::<T>::OverFlowHappens)?;
 		<OwnedContractsArray<T>>::insert((owner, old_owned_count), contract_id);
 		<OwnedContractsCount<T>>::insert(owner, new_owned_count);
 		<OwnedContractsIndex<T>>::insert(contract_id, old_owned_count);
 
 		//deposit a event
 		Self::deposit_event(RawEvent::ContractCreated(owner.clone(), contract_id.clone()));
+		if contract.start_date <= <system::Module<T>>::block_number() {
+			Self::set_status(contract_id, ContractStatus::Active);
+		}
 
 		Ok(())
 	}
 
 	fn transfer_from(from: &T::AccountId, to: &T::AccountId, contract_id: &T::Hash) -> DispatchResult {
 		//make sure sender and to are different owners
-		ensure!(from != to, "can't transfer contract to yourself");
+		ensure!(from != to, Error::<T>::TransferToSelf);
 		//make sure contract exists
-		ensure!(<Contracts<T>>::exists(contract_id), "this contract doesn't exist");
+		ensure!(<Contracts<T>>::exists(contract_id), Error::<T>::ContractNotExist);
 
-		//change owner
+		//change owner, a listing of the previous owner doesn't hold anymore
 		<ContractOwner<T>>::insert(contract_id, to);
+		<Listings<T>>::remove(contract_id);
 
 		//update owned contracts array of original owner
 		let old_owned_index_from = Self::owned_index_of(contract_id);
 		let old_owned_count_from = Self::owned_contract_count(from);
 		let new_owned_count_from = old_owned_count_from
 			.checked_sub(1)
-			.ok_or("underflow happens when owned count decrease")?;
+			.ok_or(Error::<T>::UnderFlowHappens)?;
 
 		<OwnedContractsCount<T>>::insert(from, new_owned_count_from);
 		Self::update_owned_array(from, old_owned_index_from, new_owned_count_from)?; //index of last contract in array is new_owned_count
@@ -176,7 +581,7 @@ impl<T: Trait> Module<T> {
 		let old_owned_count_to = Self::owned_contract_count(to);
 		let new_owned_count_to = old_owned_count_to
 			.checked_add(1)
-			.ok_or("overflow happends when owned count increase")?;
+			.ok_or(Error::<T>::OverFlowHappens)?;
 		<OwnedContractsCount<T>>::insert(to, new_owned_count_to);
 		<OwnedContractsArray<T>>::insert((to, old_owned_count_to), contract_id);
 
@@ -191,6 +596,148 @@ impl<T: Trait> Module<T> {
 		Ok(())
 	}
 
+	/// Most hash power `provider` is committed to at any block from `start` until `end`.
+	pub fn peak_commitment(provider: &T::AccountId, start: T::BlockNumber, end: T::BlockNumber) -> ValueHashPower {
+		//hash power starting or ending at a block, ends sorting first as a contract isn't served at its end date
+		let mut changes: Vec<(T::BlockNumber, bool, ValueHashPower)> = Vec::new();
+		for commitment in Self::commitments_of(provider) {
+			if commitment.start < end && commitment.end > start {
+				changes.push((commitment.start.max(start), true, commitment.hash_power));
+				changes.push((commitment.end, false, commitment.hash_power));
+			}
+		}
+		changes.sort();
+
+		let mut committed: ValueHashPower = 0;
+		let mut peak = 0;
+		for (_, starting, hash_power) in changes {
+			if starting {
+				committed = committed.saturating_add(hash_power);
+				peak = peak.max(committed);
+			} else {
+				committed = committed.saturating_sub(hash_power);
+			}
+		}
+		peak
+	}
+
+	/// Hash power `provider` can still sell over every block from `start` until `end`.
+	pub fn free_capacity(provider: &T::AccountId, start: T::BlockNumber, end: T::BlockNumber) -> ValueHashPower {
+		T::ServiceProviders::capacity(provider).saturating_sub(Self::peak_commitment(provider, start, end))
+	}
+
+	/// Check that `provider` may sell a contract on these terms.
+	fn ensure_can_sell(
+		provider: &T::AccountId,
+		hash_power: ValueHashPower,
+		start_date: T::BlockNumber,
+		end_date: T::BlockNumber,
+	) -> DispatchResult {
+		Self::ensure_valid_terms(hash_power, start_date, end_date)?;
+		ensure!(T::ServiceProviders::is_active(provider), Error::<T>::ProviderNotActive);
+		let free = Self::free_capacity(provider, start_date, end_date);
+		ensure!(hash_power <= free, Error::<T>::InsufficientCapacity);
+		Ok(())
+	}
+
+	/// Check that a new contract can be minted to `owner`, so that `create` doesn't fail once paid for.
+	fn ensure_can_mint(owner: &T::AccountId) -> DispatchResult {
+		let contract_id = Self::random_number(Self::nonce());
+		ensure!(!<ContractOwner<T>>::exists(contract_id), Error::<T>::ContractAlreadyExists);
+		ensure!(Self::contracts_count().checked_add(1).is_some(), Error::<T>::OverFlowHappens);
+		ensure!(Self::owned_contract_count(owner).checked_add(1).is_some(), Error::<T>::OverFlowHappens);
+		Ok(())
+	}
+
+	/// Check that `amount` can be paid to `who`: it isn't barred from being paid, and the payment doesn't open
+	/// an account below the minimum balance.
+	fn ensure_can_receive(who: &T::AccountId, amount: BalanceOf<T>) -> DispatchResult {
+		if amount.is_zero() {
+			return Ok(());
+		}
+		ensure!(T::Payees::can_receive(who), Error::<T>::CannotReceive);
+		ensure!(
+			amount >= T::Currency::minimum_balance() || !T::Currency::total_balance(who).is_zero(),
+			Error::<T>::BelowMinimumBalance
+		);
+		Ok(())
+	}
+
+	/// Mint a contract to `owner` for hash power of `provider`, which must have been checked by `ensure_can_sell`
+	/// and `ensure_can_mint`.
+	fn create(
+		owner: &T::AccountId,
+		provider: &T::AccountId,
+		hash_power: ValueHashPower,
+		start_date: T::BlockNumber,
+		end_date: T::BlockNumber,
+	) -> Result<T::Hash, DispatchError> {
+		let nonce = Self::nonce();
+		//let random_hash = (&sender,nonce).using_encoded(<T as system::Trait>::Hashing::hash);
+		let random_hash = Self::random_number(nonce);
+
+		let new_contract  = HashPowerContract {
+			id: random_hash,
+			hash_power: hash_power,
+			start_date: start_date,
+			end_date: end_date,
+			service_provider: provider.clone(),
+			status: ContractStatus::Pending,
+		};
+		//mint a contract
+		Self::mint_contract(owner, &random_hash, &new_contract)?;
+		Self::commit_hash_power(&new_contract);
+
+		<Nonce>::mutate(|nonce| *nonce += 1);
+		Ok(random_hash)
+	}
+
+	/// Number of blocks in `term` days.
+	fn term_duration(term: u32) -> Result<T::BlockNumber, DispatchError> {
+		T::BlocksPerDay::get().checked_mul(&term.into()).ok_or(Error::<T>::DurationTooLong.into())
+	}
+
+	/// Price of `hash_power` TH/s over the term of an offer, `None` on overflow.
+	pub fn offer_price(offer: &Offer<T::AccountId, BalanceOf<T>>, hash_power: ValueHashPower) -> Option<BalanceOf<T>> {
+		offer.price_per_day
+			.checked_mul(&hash_power.saturated_into())?
+			.checked_mul(&offer.term.into())
+	}
+
+	/// Commit the provider of a new contract to its hash power, dropping commitments that ended.
+	fn commit_hash_power(contract: &HashPowerContract<T::Hash, T::BlockNumber, T::AccountId>) {
+		let now = <system::Module<T>>::block_number();
+		<Commitments<T>>::mutate(&contract.service_provider, |commitments| {
+			commitments.retain(|c| c.end > now);
+			commitments.push(Commitment {
+				contract_id: contract.id,
+				hash_power: contract.hash_power,
+				start: contract.start_date,
+				end: contract.end_date,
+			});
+		});
+	}
+
+	/// End a contract early, freeing the hash power its provider committed to it.
+	fn terminate(contract_id: &T::Hash) {
+		let provider = Self::contract(contract_id).service_provider;
+		<Commitments<T>>::mutate(&provider, |commitments| commitments.retain(|c| c.contract_id != *contract_id));
+		Self::set_status(contract_id, ContractStatus::Terminated);
+	}
+
+	/// Whether a contract may still be sold on, i.e. it hasn't ended and isn't disputed.
+	fn is_tradable(contract_id: &T::Hash) -> bool {
+		match Self::contract(contract_id).status {
+			ContractStatus::Pending | ContractStatus::Active => true,
+			_ => false,
+		}
+	}
+
+	fn set_status(contract_id: &T::Hash, status: ContractStatus) {
+		<Contracts<T>>::mutate(contract_id, |contract| contract.status = status);
+		Self::deposit_event(RawEvent::ContractStatusChanged(*contract_id, status));
+	}
+
 	fn update_owned_array(owner: &T::AccountId, index_gone: u64, index_last: u64) -> DispatchResult {
 		if index_gone != index_last {
 			let last_owned_contract = Self::contracts_of((owner, index_last));
@@ -25,6 +25,15 @@ parameter_types! {
 	pub const MaximumBlockWeight: Weight = 1024;
 	pub const MaximumBlockLength: u32 = 2 * 1024;
 	pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+	pub const MinDuration: u64 = 10;
+	pub const MaxDuration: u64 = 1000;
+	pub const MinHashPower: ValueHashPower = 10;
+	pub const MaxHashPower: ValueHashPower = 100000;
+	pub const BlocksPerDay: u64 = 10;
+	pub const MaxScheduledPerBlock: u32 = 4;
+	pub const ExistentialDeposit: u64 = 0;
+	pub const TransferFee: u64 = 0;
+	pub const CreationFee: u64 = 0;
 }
 impl system::Trait for TestRuntime {
 	type Origin = Origin;
@@ -45,18 +54,68 @@ impl system::Trait for TestRuntime {
 	type ModuleToIndex = ();
 }
 
+impl balances::Trait for TestRuntime {
+	type Balance = u64;
+	type OnFreeBalanceZero = ();
+	type OnNewAccount = ();
+	type Event = ();
+	type DustRemoval = ();
+	type TransferPayment = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type TransferFee = TransferFee;
+	type CreationFee = CreationFee;
+}
+
 impl Trait for TestRuntime {
 	type Event = ();
 	type Randomness = randomness_collective_flip::Module<TestRuntime>;
+	type MinDuration = MinDuration;
+	type MaxDuration = MaxDuration;
+	type MinHashPower = MinHashPower;
+	type MaxHashPower = MaxHashPower;
+	type ServiceProviders = TestProviders;
+	type Currency = balances::Module<TestRuntime>;
+	type BlocksPerDay = BlocksPerDay;
+	type MaxScheduledPerBlock = MaxScheduledPerBlock;
+	type Payees = TestPayees;
+}
+
+/// Accounts 1 to 10 are active service providers, account 4 with a capacity of 5000.
+pub struct TestProviders;
+impl ServiceProviders<u64> for TestProviders {
+	fn is_active(who: &u64) -> bool {
+		(1..=10).contains(who)
+	}
+
+	fn capacity(who: &u64) -> ValueHashPower {
+		match *who {
+			1..=3 | 5..=10 => 1000000,
+			4 => 5000,
+			_ => 0,
+		}
+	}
+}
+
+/// Account 31 is frozen and can't be paid.
+pub struct TestPayees;
+impl Payees<u64> for TestPayees {
+	fn can_receive(who: &u64) -> bool {
+		*who != 31
+	}
 }
 
 pub type HashPowerContract = Module<TestRuntime>;
+pub type System = system::Module<TestRuntime>;
+pub type Balances = balances::Module<TestRuntime>;
 
 // This function basically just builds a genesis storage key/value store according to
 // our desired mockup.
+// Account 20 holds 1000000 to buy hash power with.
 pub fn build_ext() -> sp_io::TestExternalities {
-	system::GenesisConfig::default()
-		.build_storage::<TestRuntime>()
-		.unwrap()
-		.into()
+	let mut t = system::GenesisConfig::default().build_storage::<TestRuntime>().unwrap();
+	balances::GenesisConfig::<TestRuntime> {
+		balances: vec![(20, 1000000)],
+		vesting: vec![],
+	}.assimilate_storage(&mut t).unwrap();
+	t.into()
 }
@@ -1,7 +1,8 @@
 use super::*;
 use frame_support::{assert_noop, assert_ok};
-use mock::{build_ext, HashPowerContract, Origin};
+use mock::{build_ext, Balances, HashPowerContract, Origin, System, TestRuntime};
 use sp_core::H256;
+use sp_runtime::{traits::OnInitialize, DispatchError, Permill};
 
 #[test]
 fn it_works() {
@@ -15,7 +16,7 @@ fn test_create_single_contract_ok() {
 	build_ext().execute_with(|| {
 		//no contract before we created one
 		assert_eq!(HashPowerContract::contracts_count(), 0);
-		assert_ok!(HashPowerContract::create_contract(Origin::signed(5), 1234, 123, 456, 1));
+		assert_ok!(HashPowerContract::create_contract(Origin::signed(5), 1234, 123, 456, 5));
 		//count should be 1
 		assert_eq!(HashPowerContract::contracts_count(), 1);
 	})
@@ -24,7 +25,7 @@ fn test_create_single_contract_ok() {
 #[test]
 fn test_create_contracts_storage_ok() {
 	build_ext().execute_with(|| {
-		assert_ok!(HashPowerContract::create_contract(Origin::signed(5), 1234, 123, 456, 1));
+		assert_ok!(HashPowerContract::create_contract(Origin::signed(5), 1234, 123, 456, 5));
 		//count should be 1
 		assert_eq!(HashPowerContract::contracts_count(), 1);
 		assert_ok!(HashPowerContract::create_contract(
@@ -32,11 +33,11 @@ fn test_create_contracts_storage_ok() {
 			4321,
 			123,
 			999,
-			2
+			10
 		));
 		//count should be 2
 		assert_eq!(HashPowerContract::contracts_count(), 2);
-		assert_ok!(HashPowerContract::create_contract(Origin::signed(5), 1234, 000, 456, 3));
+		assert_ok!(HashPowerContract::create_contract(Origin::signed(5), 1234, 000, 456, 5));
 		//count should be 3
 		assert_eq!(HashPowerContract::contracts_count(), 3);
 
@@ -73,7 +74,7 @@ fn test_create_contracts_storage_ok() {
 #[test]
 fn test_transfer_single_contract_ok() {
 	build_ext().execute_with(|| {
-		assert_ok!(HashPowerContract::create_contract(Origin::signed(5), 1234, 123, 456, 1));
+		assert_ok!(HashPowerContract::create_contract(Origin::signed(5), 1234, 123, 456, 5));
 
 		let contract_hash = HashPowerContract::contract_at_index(0);
 
@@ -98,16 +99,16 @@ fn test_transfer_single_contract_ok() {
 #[test]
 fn test_transfer_contract_storage_ok() {
 	build_ext().execute_with(|| {
-		assert_ok!(HashPowerContract::create_contract(Origin::signed(5), 1234, 123, 456, 1));
+		assert_ok!(HashPowerContract::create_contract(Origin::signed(5), 1234, 123, 456, 5));
 		assert_ok!(HashPowerContract::create_contract(
 			Origin::signed(10),
 			4321,
 			123,
 			999,
-			2
+			10
 		));
-		assert_ok!(HashPowerContract::create_contract(Origin::signed(5), 1234, 000, 456, 3));
-		assert_ok!(HashPowerContract::create_contract(Origin::signed(5), 1234, 000, 456, 4));
+		assert_ok!(HashPowerContract::create_contract(Origin::signed(5), 1234, 000, 456, 5));
+		assert_ok!(HashPowerContract::create_contract(Origin::signed(5), 1234, 000, 456, 5));
 
 		let contract_hash_one = HashPowerContract::contract_at_index(0);
 		let contract_hash_two = HashPowerContract::contract_at_index(1);
@@ -155,14 +156,14 @@ fn test_transfer_contract_storage_ok() {
 #[test]
 fn test_create_contract_exist_fail() {
 	build_ext().execute_with(|| {
-		assert_ok!(HashPowerContract::create_contract(Origin::signed(5), 555, 123, 456, 1));
+		assert_ok!(HashPowerContract::create_contract(Origin::signed(5), 555, 123, 456, 5));
 
 		let contract_id = HashPowerContract::contract_at_index(0);
 		let contract = HashPowerContract::contract(contract_id);
 
 		assert_noop!(
 			HashPowerContract::mint_contract(&5, &contract_id, &contract),
-			"contract already exits"
+			Error::<TestRuntime>::ContractAlreadyExists
 		);
 	})
 }
@@ -170,11 +171,11 @@ fn test_create_contract_exist_fail() {
 #[test]
 fn test_transfer_contract_from_wrong_owner_fail() {
 	build_ext().execute_with(|| {
-		assert_ok!(HashPowerContract::create_contract(Origin::signed(5), 555, 123, 456, 1));
+		assert_ok!(HashPowerContract::create_contract(Origin::signed(5), 555, 123, 456, 5));
 		let contract_id = HashPowerContract::contract_at_index(0);
 		assert_noop!(
 			HashPowerContract::transfer_contract(Origin::signed(4), 6, contract_id),
-			"can only transfer your own contract"
+			Error::<TestRuntime>::NotContractOwner
 		);
 	})
 }
@@ -182,11 +183,11 @@ fn test_transfer_contract_from_wrong_owner_fail() {
 #[test]
 fn test_transfer_to_owner_fail() {
 	build_ext().execute_with(|| {
-		assert_ok!(HashPowerContract::create_contract(Origin::signed(5), 555, 123, 456, 1));
+		assert_ok!(HashPowerContract::create_contract(Origin::signed(5), 555, 123, 456, 5));
 		let contract_id = HashPowerContract::contract_at_index(0);
 		assert_noop!(
 			HashPowerContract::transfer_contract(Origin::signed(5), 5, contract_id),
-			"can't transfer contract to yourself"
+			Error::<TestRuntime>::TransferToSelf
 		);
 	})
 }
@@ -194,11 +195,362 @@ fn test_transfer_to_owner_fail() {
 #[test]
 fn test_tranfer_contract_nonexist_fail() {
 	build_ext().execute_with(|| {
-		assert_ok!(HashPowerContract::create_contract(Origin::signed(5), 555, 123, 456, 1));
+		assert_ok!(HashPowerContract::create_contract(Origin::signed(5), 555, 123, 456, 5));
 		let contract_id = HashPowerContract::contract_at_index(0);
 		assert_noop!(
 			HashPowerContract::transfer_from(&5, &6, &H256::from_low_u64_be(100)),
-			"this contract doesn't exist"
+			Error::<TestRuntime>::ContractNotExist
 		);
 	})
 }
+
+#[test]
+fn test_create_contract_invalid_terms_fail() {
+	build_ext().execute_with(|| {
+		assert_noop!(HashPowerContract::create_contract(Origin::signed(5), 0, 123, 456, 5), Error::<TestRuntime>::ZeroHashPower);
+		assert_noop!(HashPowerContract::create_contract(Origin::signed(5), 5, 123, 456, 5), Error::<TestRuntime>::HashPowerTooLow);
+		assert_noop!(HashPowerContract::create_contract(Origin::signed(5), 100001, 123, 456, 5), Error::<TestRuntime>::HashPowerTooHigh);
+		assert_noop!(HashPowerContract::create_contract(Origin::signed(5), 1234, 456, 456, 5), Error::<TestRuntime>::InvalidPeriod);
+		assert_noop!(HashPowerContract::create_contract(Origin::signed(5), 1234, 456, 123, 5), Error::<TestRuntime>::InvalidPeriod);
+		assert_noop!(HashPowerContract::create_contract(Origin::signed(5), 1234, 123, 132, 5), Error::<TestRuntime>::DurationTooShort);
+		assert_noop!(HashPowerContract::create_contract(Origin::signed(5), 1234, 123, 1124, 5), Error::<TestRuntime>::DurationTooLong);
+
+		//bounds are inclusive
+		assert_ok!(HashPowerContract::create_contract(Origin::signed(5), 10, 123, 133, 5));
+		assert_ok!(HashPowerContract::create_contract(Origin::signed(5), 100000, 123, 1123, 5));
+	})
+}
+
+#[test]
+fn test_create_contract_start_in_past_fail() {
+	build_ext().execute_with(|| {
+		System::set_block_number(200);
+		assert_noop!(HashPowerContract::create_contract(Origin::signed(5), 1234, 123, 456, 5), Error::<TestRuntime>::StartInPast);
+		assert_ok!(HashPowerContract::create_contract(Origin::signed(5), 1234, 200, 456, 5));
+	})
+}
+
+fn run_to_block(n: u64) {
+	while System::block_number() < n {
+		System::set_block_number(System::block_number() + 1);
+		HashPowerContract::on_initialize(System::block_number());
+	}
+}
+
+#[test]
+fn test_contract_status_schedule_ok() {
+	build_ext().execute_with(|| {
+		assert_ok!(HashPowerContract::create_contract(Origin::signed(5), 1234, 100, 200, 5));
+		let contract_id = HashPowerContract::contract_at_index(0);
+		assert_eq!(HashPowerContract::contract(contract_id).status, ContractStatus::Pending);
+		assert_eq!(HashPowerContract::contracts_starting(100), vec![contract_id]);
+		assert_eq!(HashPowerContract::contracts_ending(200), vec![contract_id]);
+
+		run_to_block(99);
+		assert_eq!(HashPowerContract::contract(contract_id).status, ContractStatus::Pending);
+		run_to_block(100);
+		assert_eq!(HashPowerContract::contract(contract_id).status, ContractStatus::Active);
+		assert_eq!(HashPowerContract::contracts_starting(100), vec![]);
+		run_to_block(200);
+		assert_eq!(HashPowerContract::contract(contract_id).status, ContractStatus::Expired);
+		assert_eq!(HashPowerContract::contracts_ending(200), vec![]);
+	})
+}
+
+#[test]
+fn test_contract_starting_now_active_ok() {
+	build_ext().execute_with(|| {
+		System::set_block_number(100);
+		assert_ok!(HashPowerContract::create_contract(Origin::signed(5), 1234, 100, 200, 5));
+		let contract_id = HashPowerContract::contract_at_index(0);
+		assert_eq!(HashPowerContract::contract(contract_id).status, ContractStatus::Active);
+		assert_eq!(HashPowerContract::contracts_starting(100), vec![]);
+	})
+}
+
+#[test]
+fn test_schedule_full_fail() {
+	build_ext().execute_with(|| {
+		for _ in 0..4 {
+			assert_ok!(HashPowerContract::create_contract(Origin::signed(5), 1234, 100, 200, 5));
+		}
+		assert_noop!(HashPowerContract::create_contract(Origin::signed(5), 1234, 100, 300, 5), Error::<TestRuntime>::ScheduleFull);
+		assert_noop!(HashPowerContract::create_contract(Origin::signed(5), 1234, 150, 200, 5), Error::<TestRuntime>::ScheduleFull);
+		assert_ok!(HashPowerContract::create_contract(Origin::signed(5), 1234, 150, 300, 5));
+	})
+}
+
+#[test]
+fn test_dispute_contract_ok() {
+	build_ext().execute_with(|| {
+		assert_ok!(HashPowerContract::create_contract(Origin::signed(5), 1234, 100, 200, 5));
+		let contract_id = HashPowerContract::contract_at_index(0);
+		assert_noop!(HashPowerContract::dispute_contract(Origin::signed(5), contract_id), Error::<TestRuntime>::InvalidStatus);
+
+		run_to_block(150);
+		assert_noop!(HashPowerContract::dispute_contract(Origin::signed(4), contract_id), Error::<TestRuntime>::NotContractOwner);
+		assert_ok!(HashPowerContract::dispute_contract(Origin::signed(5), contract_id));
+		assert_eq!(HashPowerContract::contract(contract_id).status, ContractStatus::Disputed);
+
+		//a disputed contract doesn't expire
+		run_to_block(200);
+		assert_eq!(HashPowerContract::contract(contract_id).status, ContractStatus::Disputed);
+
+		assert_noop!(HashPowerContract::resolve_dispute(Origin::signed(5), contract_id, false), DispatchError::BadOrigin);
+		assert_ok!(HashPowerContract::resolve_dispute(Origin::ROOT, contract_id, false));
+		assert_eq!(HashPowerContract::contract(contract_id).status, ContractStatus::Expired);
+	})
+}
+
+#[test]
+fn test_terminate_contract_ok() {
+	build_ext().execute_with(|| {
+		assert_ok!(HashPowerContract::create_contract(Origin::signed(5), 1234, 100, 200, 5));
+		let contract_id = HashPowerContract::contract_at_index(0);
+		run_to_block(150);
+		assert_ok!(HashPowerContract::dispute_contract(Origin::signed(5), contract_id));
+		assert_ok!(HashPowerContract::resolve_dispute(Origin::ROOT, contract_id, true));
+		assert_eq!(HashPowerContract::contract(contract_id).status, ContractStatus::Terminated);
+		assert_noop!(HashPowerContract::terminate_contract(Origin::ROOT, contract_id), Error::<TestRuntime>::InvalidStatus);
+
+		//a terminated contract doesn't expire
+		run_to_block(200);
+		assert_eq!(HashPowerContract::contract(contract_id).status, ContractStatus::Terminated);
+
+		assert_ok!(HashPowerContract::create_contract(Origin::signed(5), 1234, 300, 400, 5));
+		let contract_id = HashPowerContract::contract_at_index(1);
+		assert_ok!(HashPowerContract::terminate_contract(Origin::ROOT, contract_id));
+		run_to_block(300);
+		assert_eq!(HashPowerContract::contract(contract_id).status, ContractStatus::Terminated);
+	})
+}
+
+#[test]
+fn test_create_contract_inactive_provider_fail() {
+	build_ext().execute_with(|| {
+		assert_noop!(HashPowerContract::create_contract(Origin::signed(11), 1234, 123, 456, 11), Error::<TestRuntime>::ProviderNotActive);
+	})
+}
+
+#[test]
+fn test_create_contract_for_other_provider_fail() {
+	build_ext().execute_with(|| {
+		//minting against someone else's capacity would take it for free
+		assert_noop!(HashPowerContract::create_contract(Origin::signed(5), 1234, 123, 456, 1), Error::<TestRuntime>::NotServiceProvider);
+		assert_noop!(HashPowerContract::create_contract(Origin::signed(20), 1234, 123, 456, 4), Error::<TestRuntime>::NotServiceProvider);
+		assert_eq!(HashPowerContract::free_capacity(&4, 123, 456), 5000);
+	})
+}
+
+#[test]
+fn test_capacity_overlapping_contracts_ok() {
+	build_ext().execute_with(|| {
+		assert_ok!(HashPowerContract::create_contract(Origin::signed(4), 3000, 100, 200, 4));
+		assert_ok!(HashPowerContract::create_contract(Origin::signed(4), 1000, 150, 300, 4));
+		assert_eq!(HashPowerContract::free_capacity(&4, 100, 300), 1000);
+		assert_eq!(HashPowerContract::free_capacity(&4, 100, 150), 2000);
+		assert_eq!(HashPowerContract::free_capacity(&4, 200, 300), 4000);
+
+		//the overlap of both contracts can't take more
+		assert_noop!(
+			HashPowerContract::create_contract(Origin::signed(4), 2000, 180, 250, 4),
+			Error::<TestRuntime>::InsufficientCapacity
+		);
+		assert_ok!(HashPowerContract::create_contract(Origin::signed(4), 1000, 180, 250, 4));
+		//a contract starting as another ends doesn't overlap it
+		assert_ok!(HashPowerContract::create_contract(Origin::signed(4), 3000, 300, 400, 4));
+		assert_ok!(HashPowerContract::create_contract(Origin::signed(4), 2000, 250, 300, 4));
+		assert_eq!(HashPowerContract::free_capacity(&4, 100, 400), 0);
+	})
+}
+
+#[test]
+fn test_capacity_freed_ok() {
+	build_ext().execute_with(|| {
+		assert_ok!(HashPowerContract::create_contract(Origin::signed(4), 5000, 100, 200, 4));
+		assert_noop!(
+			HashPowerContract::create_contract(Origin::signed(4), 5000, 150, 250, 4),
+			Error::<TestRuntime>::InsufficientCapacity
+		);
+
+		//a terminated contract frees its hash power
+		let contract_id = HashPowerContract::contract_at_index(0);
+		assert_ok!(HashPowerContract::terminate_contract(Origin::ROOT, contract_id));
+		assert_eq!(HashPowerContract::commitments_of(4), vec![]);
+		assert_ok!(HashPowerContract::create_contract(Origin::signed(4), 5000, 150, 250, 4));
+
+		//ended commitments are dropped with the next one
+		run_to_block(250);
+		assert_ok!(HashPowerContract::create_contract(Origin::signed(4), 5000, 250, 350, 4));
+		assert_eq!(HashPowerContract::commitments_of(4).len(), 1);
+	})
+}
+
+#[test]
+fn test_create_offer_ok() {
+	build_ext().execute_with(|| {
+		assert_noop!(HashPowerContract::create_offer(Origin::signed(11), 2, 4000, 30), Error::<TestRuntime>::ProviderNotActive);
+		assert_noop!(HashPowerContract::create_offer(Origin::signed(4), 0, 4000, 30), Error::<TestRuntime>::ZeroPrice);
+		assert_noop!(HashPowerContract::create_offer(Origin::signed(4), 2, 0, 30), Error::<TestRuntime>::ZeroHashPower);
+		assert_noop!(HashPowerContract::create_offer(Origin::signed(4), 2, 4000, 0), Error::<TestRuntime>::DurationTooShort);
+		assert_noop!(HashPowerContract::create_offer(Origin::signed(4), 2, 4000, 101), Error::<TestRuntime>::DurationTooLong);
+
+		assert_ok!(HashPowerContract::create_offer(Origin::signed(4), 2, 4000, 30));
+		assert_eq!(HashPowerContract::next_offer_id(), 1);
+		let offer = HashPowerContract::offer(0).unwrap();
+		assert_eq!(offer.provider, 4);
+		assert_eq!(HashPowerContract::offer_price(&offer, 1000), Some(60000));
+	})
+}
+
+#[test]
+fn test_purchase_offer_ok() {
+	build_ext().execute_with(|| {
+		assert_ok!(HashPowerContract::create_offer(Origin::signed(4), 2, 4000, 30));
+		assert_noop!(HashPowerContract::purchase_offer(Origin::signed(4), 0, 1000, 100), Error::<TestRuntime>::BuyerIsProvider);
+		assert_noop!(HashPowerContract::purchase_offer(Origin::signed(20), 0, 4001, 100), Error::<TestRuntime>::ExceedsOffer);
+
+		assert_ok!(HashPowerContract::purchase_offer(Origin::signed(20), 0, 1000, 100));
+		//paid 2 per TH/s per day, for 1000 TH/s over 30 days
+		assert_eq!(Balances::free_balance(&20), 1000000 - 60000);
+		assert_eq!(Balances::free_balance(&4), 60000);
+
+		let contract_id = HashPowerContract::contract_at_index(0);
+		let contract = HashPowerContract::contract(contract_id);
+		assert_eq!(HashPowerContract::owner_of(contract_id), Some(20));
+		assert_eq!(contract.service_provider, 4);
+		assert_eq!(contract.hash_power, 1000);
+		assert_eq!((contract.start_date, contract.end_date), (100, 400));
+		assert_eq!(HashPowerContract::offer(0).unwrap().quantity, 3000);
+
+		//buying out the rest closes the offer
+		assert_ok!(HashPowerContract::purchase_offer(Origin::signed(20), 0, 3000, 100));
+		assert_eq!(HashPowerContract::offer(0), None);
+		assert_eq!(HashPowerContract::owned_contract_count(20), 2);
+	})
+}
+
+#[test]
+fn test_purchase_offer_fail() {
+	build_ext().execute_with(|| {
+		assert_ok!(HashPowerContract::create_offer(Origin::signed(4), 2, 4000, 30));
+
+		//the provider sold part of its capacity elsewhere
+		assert_ok!(HashPowerContract::create_contract(Origin::signed(4), 2000, 100, 400, 4));
+		assert_noop!(
+			HashPowerContract::purchase_offer(Origin::signed(20), 0, 3001, 100),
+			Error::<TestRuntime>::InsufficientCapacity
+		);
+
+		//the buyer can't pay
+		assert_noop!(
+			HashPowerContract::purchase_offer(Origin::signed(21), 0, 1000, 100),
+			Error::<TestRuntime>::InsufficientBalance
+		);
+		assert_eq!(HashPowerContract::contracts_count(), 1);
+		assert_eq!(HashPowerContract::offer(0).unwrap().quantity, 4000);
+	})
+}
+
+#[test]
+fn test_cancel_offer_ok() {
+	build_ext().execute_with(|| {
+		assert_ok!(HashPowerContract::create_offer(Origin::signed(4), 2, 4000, 30));
+		assert_noop!(HashPowerContract::cancel_offer(Origin::signed(20), 0), Error::<TestRuntime>::NotOfferProvider);
+
+		assert_ok!(HashPowerContract::cancel_offer(Origin::signed(4), 0));
+		assert_eq!(HashPowerContract::offer(0), None);
+		assert_noop!(HashPowerContract::purchase_offer(Origin::signed(20), 0, 1000, 100), Error::<TestRuntime>::OfferNotExist);
+	})
+}
+
+fn listed_contract() -> H256 {
+	assert_ok!(HashPowerContract::create_contract(Origin::signed(5), 1234, 100, 200, 5));
+	let contract_id = HashPowerContract::contract_at_index(0);
+	assert_ok!(HashPowerContract::list_for_sale(Origin::signed(5), contract_id, 1000));
+	contract_id
+}
+
+#[test]
+fn test_list_for_sale_ok() {
+	build_ext().execute_with(|| {
+		assert_ok!(HashPowerContract::create_contract(Origin::signed(5), 1234, 100, 200, 5));
+		let contract_id = HashPowerContract::contract_at_index(0);
+		assert_noop!(HashPowerContract::list_for_sale(Origin::signed(6), contract_id, 1000), Error::<TestRuntime>::NotContractOwner);
+		assert_noop!(HashPowerContract::list_for_sale(Origin::signed(5), contract_id, 0), Error::<TestRuntime>::ZeroPrice);
+		assert_noop!(HashPowerContract::update_price(Origin::signed(5), contract_id, 500), Error::<TestRuntime>::NotListed);
+
+		assert_ok!(HashPowerContract::list_for_sale(Origin::signed(5), contract_id, 1000));
+		assert_eq!(HashPowerContract::listing(contract_id), Some(1000));
+		assert_ok!(HashPowerContract::update_price(Origin::signed(5), contract_id, 500));
+		assert_eq!(HashPowerContract::listing(contract_id), Some(500));
+
+		assert_noop!(HashPowerContract::cancel_listing(Origin::signed(6), contract_id), Error::<TestRuntime>::NotContractOwner);
+		assert_ok!(HashPowerContract::cancel_listing(Origin::signed(5), contract_id));
+		assert_eq!(HashPowerContract::listing(contract_id), None);
+		assert_noop!(HashPowerContract::buy(Origin::signed(20), contract_id, 500), Error::<TestRuntime>::NotListed);
+	})
+}
+
+#[test]
+fn test_buy_ok() {
+	build_ext().execute_with(|| {
+		let contract_id = listed_contract();
+		assert_noop!(HashPowerContract::buy(Origin::signed(5), contract_id, 1000), Error::<TestRuntime>::TransferToSelf);
+		assert_noop!(HashPowerContract::buy(Origin::signed(20), contract_id, 999), Error::<TestRuntime>::PriceTooHigh);
+		assert_noop!(HashPowerContract::buy(Origin::signed(21), contract_id, 1000), Error::<TestRuntime>::InsufficientBalance);
+
+		assert_ok!(HashPowerContract::buy(Origin::signed(20), contract_id, 1000));
+		assert_eq!(HashPowerContract::owner_of(contract_id), Some(20));
+		assert_eq!(HashPowerContract::owned_contract_count(5), 0);
+		assert_eq!(HashPowerContract::contracts_of((20, 0)), contract_id);
+		assert_eq!(HashPowerContract::listing(contract_id), None);
+		//no fee without a treasury
+		assert_eq!(Balances::free_balance(&20), 1000000 - 1000);
+		assert_eq!(Balances::free_balance(&5), 1000);
+	})
+}
+
+#[test]
+fn test_buy_with_market_fee_ok() {
+	build_ext().execute_with(|| {
+		assert_noop!(HashPowerContract::set_market_fee(Origin::signed(5), Permill::from_percent(2), Some(30)), DispatchError::BadOrigin);
+		assert_ok!(HashPowerContract::set_market_fee(Origin::ROOT, Permill::from_percent(2), Some(30)));
+
+		let contract_id = listed_contract();
+		assert_ok!(HashPowerContract::buy(Origin::signed(20), contract_id, 1000));
+		assert_eq!(Balances::free_balance(&20), 1000000 - 1000);
+		assert_eq!(Balances::free_balance(&5), 980);
+		assert_eq!(Balances::free_balance(&30), 20);
+	})
+}
+
+#[test]
+fn test_buy_frozen_treasury_fail() {
+	build_ext().execute_with(|| {
+		assert_ok!(HashPowerContract::set_market_fee(Origin::ROOT, Permill::from_percent(2), Some(31)));
+
+		//the seller isn't paid without the treasury taking its fee
+		let contract_id = listed_contract();
+		assert_noop!(HashPowerContract::buy(Origin::signed(20), contract_id, 1000), Error::<TestRuntime>::CannotReceive);
+		assert_eq!(Balances::free_balance(&20), 1000000);
+		assert_eq!(Balances::free_balance(&5), 0);
+		assert_eq!(HashPowerContract::owner_of(contract_id), Some(5));
+	})
+}
+
+#[test]
+fn test_listing_dropped_fail() {
+	build_ext().execute_with(|| {
+		//a gifted contract is no longer for sale
+		let contract_id = listed_contract();
+		assert_ok!(HashPowerContract::transfer_contract(Origin::signed(5), 6, contract_id));
+		assert_noop!(HashPowerContract::buy(Origin::signed(20), contract_id, 1000), Error::<TestRuntime>::NotListed);
+
+		//nor is a disputed one
+		assert_ok!(HashPowerContract::list_for_sale(Origin::signed(6), contract_id, 1000));
+		run_to_block(100);
+		assert_ok!(HashPowerContract::dispute_contract(Origin::signed(6), contract_id));
+		assert_noop!(HashPowerContract::buy(Origin::signed(20), contract_id, 1000), Error::<TestRuntime>::InvalidStatus);
+	})
+}
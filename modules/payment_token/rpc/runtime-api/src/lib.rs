@@ -0,0 +1,24 @@
+//! Runtime API definition for the payment token module.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	/// Queries over the balances and supply of the payment token assets.
+	pub trait PaymentTokenApi<AssetId, AccountId, Balance> where
+		AssetId: Codec,
+		AccountId: Codec,
+		Balance: Codec,
+	{
+		fn free_balance(asset_id: AssetId, who: AccountId) -> Balance;
+		fn reserved_balance(asset_id: AssetId, who: AccountId) -> Balance;
+		/// Free plus reserved balance.
+		fn total_balance(asset_id: AssetId, who: AccountId) -> Balance;
+		fn total_supply(asset_id: AssetId) -> Balance;
+		fn token_owner(asset_id: AssetId) -> Option<AccountId>;
+		/// (name, symbol, decimals) of the asset.
+		fn metadata(asset_id: AssetId) -> (Vec<u8>, Vec<u8>, u8);
+	}
+}
@@ -0,0 +1,126 @@
+//! JSON-RPC interface for the payment token module.
+
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+pub use payment_token_rpc_runtime_api::PaymentTokenApi as PaymentTokenRuntimeApi;
+
+#[rpc]
+pub trait PaymentTokenApi<BlockHash, AssetId, AccountId, Balance> {
+	#[rpc(name = "paymentToken_freeBalance")]
+	fn free_balance(&self, asset_id: AssetId, who: AccountId, at: Option<BlockHash>) -> Result<Balance>;
+
+	#[rpc(name = "paymentToken_reservedBalance")]
+	fn reserved_balance(&self, asset_id: AssetId, who: AccountId, at: Option<BlockHash>) -> Result<Balance>;
+
+	#[rpc(name = "paymentToken_totalBalance")]
+	fn total_balance(&self, asset_id: AssetId, who: AccountId, at: Option<BlockHash>) -> Result<Balance>;
+
+	#[rpc(name = "paymentToken_totalSupply")]
+	fn total_supply(&self, asset_id: AssetId, at: Option<BlockHash>) -> Result<Balance>;
+
+	#[rpc(name = "paymentToken_tokenOwner")]
+	fn token_owner(&self, asset_id: AssetId, at: Option<BlockHash>) -> Result<Option<AccountId>>;
+
+	#[rpc(name = "paymentToken_metadata")]
+	fn metadata(&self, asset_id: AssetId, at: Option<BlockHash>) -> Result<TokenMetadata>;
+}
+
+/// How an asset is displayed.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenMetadata {
+	pub name: String,
+	pub symbol: String,
+	pub decimals: u8,
+}
+
+/// Error code returned when the runtime call fails.
+const RUNTIME_ERROR: i64 = 1;
+
+/// Payment token queries backed by the runtime API.
+pub struct PaymentToken<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> PaymentToken<C, B> {
+	pub fn new(client: Arc<C>) -> Self {
+		PaymentToken {
+			client,
+			_marker: Default::default(),
+		}
+	}
+}
+
+fn runtime_error(e: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(RUNTIME_ERROR),
+		message: "Unable to query payment token.".into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
+
+impl<C, Block, AssetId, AccountId, Balance> PaymentTokenApi<<Block as BlockT>::Hash, AssetId, AccountId, Balance>
+	for PaymentToken<C, Block>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static + ProvideRuntimeApi + HeaderBackend<Block>,
+	C::Api: PaymentTokenRuntimeApi<Block, AssetId, AccountId, Balance>,
+	AssetId: Codec,
+	AccountId: Codec,
+	Balance: Codec,
+{
+	fn free_balance(&self, asset_id: AssetId, who: AccountId, at: Option<<Block as BlockT>::Hash>) -> Result<Balance> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.free_balance(&at, asset_id, who).map_err(runtime_error)
+	}
+
+	fn reserved_balance(
+		&self,
+		asset_id: AssetId,
+		who: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Balance> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.reserved_balance(&at, asset_id, who).map_err(runtime_error)
+	}
+
+	fn total_balance(&self, asset_id: AssetId, who: AccountId, at: Option<<Block as BlockT>::Hash>) -> Result<Balance> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.total_balance(&at, asset_id, who).map_err(runtime_error)
+	}
+
+	fn total_supply(&self, asset_id: AssetId, at: Option<<Block as BlockT>::Hash>) -> Result<Balance> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.total_supply(&at, asset_id).map_err(runtime_error)
+	}
+
+	fn token_owner(&self, asset_id: AssetId, at: Option<<Block as BlockT>::Hash>) -> Result<Option<AccountId>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.token_owner(&at, asset_id).map_err(runtime_error)
+	}
+
+	fn metadata(&self, asset_id: AssetId, at: Option<<Block as BlockT>::Hash>) -> Result<TokenMetadata> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		let (name, symbol, decimals) = api.metadata(&at, asset_id).map_err(runtime_error)?;
+		Ok(TokenMetadata {
+			name: String::from_utf8_lossy(&name).into_owned(),
+			symbol: String::from_utf8_lossy(&symbol).into_owned(),
+			decimals,
+		})
+	}
+}
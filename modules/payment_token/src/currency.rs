@@ -0,0 +1,469 @@
+//! `Currency`, `ReservableCurrency` and `LockableCurrency` on top of one asset of the payment token,
+//! so that it can be plugged into any pallet expecting a `T::Currency`.
+
+use crate::{
+	BalanceLock, Error, Locks, Module, RawEvent, Reserved, TotalSupply, Trait,
+	Balance as FreeBalance,
+};
+use frame_support::{
+	dispatch::{DispatchError, DispatchResult},
+	traits::{
+		Currency, ExistenceRequirement, Get, Imbalance, LockIdentifier, LockableCurrency, ReservableCurrency,
+		SignedImbalance, TryDrop, WithdrawReason, WithdrawReasons,
+	},
+	ensure, StorageDoubleMap, StorageMap,
+};
+use sp_runtime::traits::{Bounded, CheckedAdd, CheckedSub, Saturating, Zero};
+use sp_std::{cmp, marker::PhantomData, mem, prelude::*, result};
+
+/// The asset `A` of the payment token, seen as a single currency.
+///
+/// Reserves made through `ReservableCurrency` all go to the untagged (default id) reserve, and only that reserve
+/// is touched when slashing, unreserving or repatriating through it.
+///
+/// Withdrawals, deposits, transfers and reserve moves are refused while the asset is paused or the account
+/// frozen, and `deposit_creating` credits nothing then. Slashing and `make_free_balance_be` can't fail, so they
+/// go through regardless.
+pub struct AssetCurrency<T, A>(PhantomData<(T, A)>);
+
+/// Opaque, move-only struct with private fields that serves as a token denoting that
+/// funds have been created without any equal and opposite accounting.
+#[must_use]
+pub struct PositiveImbalance<T: Trait, A: Get<T::AssetId>>(T::Balance, PhantomData<A>);
+
+impl<T: Trait, A: Get<T::AssetId>> PositiveImbalance<T, A> {
+	/// Create a new positive imbalance from a balance.
+	pub fn new(amount: T::Balance) -> Self {
+		PositiveImbalance(amount, PhantomData)
+	}
+}
+
+/// Opaque, move-only struct with private fields that serves as a token denoting that
+/// funds have been destroyed without any equal and opposite accounting.
+#[must_use]
+pub struct NegativeImbalance<T: Trait, A: Get<T::AssetId>>(T::Balance, PhantomData<A>);
+
+impl<T: Trait, A: Get<T::AssetId>> NegativeImbalance<T, A> {
+	/// Create a new negative imbalance from a balance.
+	pub fn new(amount: T::Balance) -> Self {
+		NegativeImbalance(amount, PhantomData)
+	}
+}
+
+impl<T: Trait, A: Get<T::AssetId>> TryDrop for PositiveImbalance<T, A> {
+	fn try_drop(self) -> result::Result<(), Self> {
+		self.drop_zero()
+	}
+}
+
+impl<T: Trait, A: Get<T::AssetId>> Imbalance<T::Balance> for PositiveImbalance<T, A> {
+	type Opposite = NegativeImbalance<T, A>;
+
+	fn zero() -> Self {
+		Self::new(Zero::zero())
+	}
+	fn drop_zero(self) -> result::Result<(), Self> {
+		if self.0.is_zero() {
+			Ok(())
+		} else {
+			Err(self)
+		}
+	}
+	fn split(self, amount: T::Balance) -> (Self, Self) {
+		let first = self.0.min(amount);
+		let second = self.0 - first;
+
+		mem::forget(self);
+		(Self::new(first), Self::new(second))
+	}
+	fn merge(mut self, other: Self) -> Self {
+		self.0 = self.0.saturating_add(other.0);
+		mem::forget(other);
+
+		self
+	}
+	fn subsume(&mut self, other: Self) {
+		self.0 = self.0.saturating_add(other.0);
+		mem::forget(other);
+	}
+	fn offset(self, other: Self::Opposite) -> result::Result<Self, Self::Opposite> {
+		let (a, b) = (self.0, other.0);
+		mem::forget((self, other));
+
+		if a >= b {
+			Ok(Self::new(a - b))
+		} else {
+			Err(NegativeImbalance::new(b - a))
+		}
+	}
+	fn peek(&self) -> T::Balance {
+		self.0
+	}
+}
+
+impl<T: Trait, A: Get<T::AssetId>> TryDrop for NegativeImbalance<T, A> {
+	fn try_drop(self) -> result::Result<(), Self> {
+		self.drop_zero()
+	}
+}
+
+impl<T: Trait, A: Get<T::AssetId>> Imbalance<T::Balance> for NegativeImbalance<T, A> {
+	type Opposite = PositiveImbalance<T, A>;
+
+	fn zero() -> Self {
+		Self::new(Zero::zero())
+	}
+	fn drop_zero(self) -> result::Result<(), Self> {
+		if self.0.is_zero() {
+			Ok(())
+		} else {
+			Err(self)
+		}
+	}
+	fn split(self, amount: T::Balance) -> (Self, Self) {
+		let first = self.0.min(amount);
+		let second = self.0 - first;
+
+		mem::forget(self);
+		(Self::new(first), Self::new(second))
+	}
+	fn merge(mut self, other: Self) -> Self {
+		self.0 = self.0.saturating_add(other.0);
+		mem::forget(other);
+
+		self
+	}
+	fn subsume(&mut self, other: Self) {
+		self.0 = self.0.saturating_add(other.0);
+		mem::forget(other);
+	}
+	fn offset(self, other: Self::Opposite) -> result::Result<Self, Self::Opposite> {
+		let (a, b) = (self.0, other.0);
+		mem::forget((self, other));
+
+		if a >= b {
+			Ok(Self::new(a - b))
+		} else {
+			Err(PositiveImbalance::new(b - a))
+		}
+	}
+	fn peek(&self) -> T::Balance {
+		self.0
+	}
+}
+
+impl<T: Trait, A: Get<T::AssetId>> Drop for PositiveImbalance<T, A> {
+	/// Basic drop handler will just square up the total supply.
+	fn drop(&mut self) {
+		<Module<T>>::snapshot_supply(&A::get());
+		<TotalSupply<T>>::mutate(&A::get(), |v| *v = v.saturating_add(self.0));
+	}
+}
+
+impl<T: Trait, A: Get<T::AssetId>> Drop for NegativeImbalance<T, A> {
+	/// Basic drop handler will just square up the total supply.
+	fn drop(&mut self) {
+		<Module<T>>::snapshot_supply(&A::get());
+		<TotalSupply<T>>::mutate(&A::get(), |v| *v = v.saturating_sub(self.0));
+	}
+}
+
+impl<T: Trait, A: Get<T::AssetId>> AssetCurrency<T, A> {
+	fn untagged_reserve_of(who: &T::AccountId) -> T::Balance {
+		<Module<T>>::named_reserve_of(&A::get(), &(who.clone(), T::ReserveIdentifier::default()))
+	}
+
+	/// Take up to `value` out of the untagged reserve of `who` without touching the total supply,
+	/// returning what was actually taken.
+	fn take_untagged_reserve(who: &T::AccountId, value: T::Balance) -> T::Balance {
+		let asset_id = A::get();
+		let untagged = Self::untagged_reserve_of(who);
+		let actual = cmp::min(untagged, value);
+		if actual.is_zero() {
+			return actual;
+		}
+
+		let default_id = T::ReserveIdentifier::default();
+		<Module<T>>::snapshot_account(&asset_id, who);
+		<Reserved<T>>::mutate(&asset_id, who, |v| *v = v.saturating_sub(actual));
+		<Module<T>>::set_named_reserve(&asset_id, who, &default_id, untagged - actual);
+
+		<Module<T>>::deposit_event(RawEvent::ReserveSlashed(asset_id, who.clone(), default_id, actual));
+		actual
+	}
+
+	/// Make sure taking `value` out of `who`'s free balance doesn't reap it when it has to be kept alive.
+	fn ensure_keep_alive(who: &T::AccountId, value: Self::Balance, liveness: ExistenceRequirement) -> DispatchResult {
+		if liveness == ExistenceRequirement::KeepAlive {
+			let free_new = Self::free_balance(who).saturating_sub(value);
+			ensure!(!<Module<T>>::is_dust(&A::get(), who, free_new), Error::<T>::BelowMinimumBalance);
+		}
+		Ok(())
+	}
+}
+
+impl<T: Trait, A: Get<T::AssetId>> Currency<T::AccountId> for AssetCurrency<T, A> {
+	type Balance = T::Balance;
+	type PositiveImbalance = PositiveImbalance<T, A>;
+	type NegativeImbalance = NegativeImbalance<T, A>;
+
+	fn total_balance(who: &T::AccountId) -> Self::Balance {
+		Self::free_balance(who).saturating_add(<Module<T>>::reserved_balance_of(&A::get(), who))
+	}
+
+	fn can_slash(who: &T::AccountId, value: Self::Balance) -> bool {
+		Self::free_balance(who) >= value
+	}
+
+	fn total_issuance() -> Self::Balance {
+		<Module<T>>::total_supply(&A::get())
+	}
+
+	fn minimum_balance() -> Self::Balance {
+		<Module<T>>::minimum_balance(&A::get())
+	}
+
+	fn burn(mut amount: Self::Balance) -> Self::PositiveImbalance {
+		<Module<T>>::snapshot_supply(&A::get());
+		<TotalSupply<T>>::mutate(&A::get(), |issued| {
+			*issued = issued.checked_sub(&amount).unwrap_or_else(|| {
+				amount = *issued;
+				Zero::zero()
+			});
+		});
+		PositiveImbalance::new(amount)
+	}
+
+	fn issue(mut amount: Self::Balance) -> Self::NegativeImbalance {
+		<Module<T>>::snapshot_supply(&A::get());
+		<TotalSupply<T>>::mutate(&A::get(), |issued| {
+			*issued = issued.checked_add(&amount).unwrap_or_else(|| {
+				amount = Self::Balance::max_value() - *issued;
+				Self::Balance::max_value()
+			})
+		});
+		NegativeImbalance::new(amount)
+	}
+
+	fn free_balance(who: &T::AccountId) -> Self::Balance {
+		<Module<T>>::free_balance_of(&A::get(), who)
+	}
+
+	fn ensure_can_withdraw(
+		who: &T::AccountId,
+		_amount: Self::Balance,
+		reasons: WithdrawReasons,
+		new_balance: Self::Balance,
+	) -> DispatchResult {
+		<Module<T>>::ensure_can_withdraw(&A::get(), who, reasons, new_balance)
+	}
+
+	fn transfer(
+		source: &T::AccountId,
+		dest: &T::AccountId,
+		value: Self::Balance,
+		existence_requirement: ExistenceRequirement,
+	) -> DispatchResult {
+		if value.is_zero() || source == dest {
+			return Ok(());
+		}
+		Self::ensure_keep_alive(source, value, existence_requirement)?;
+		<Module<T>>::transfer(A::get(), source.clone(), dest.clone(), value)
+	}
+
+	fn slash(who: &T::AccountId, value: Self::Balance) -> (Self::NegativeImbalance, Self::Balance) {
+		let free_balance = Self::free_balance(who);
+		let free_slash = cmp::min(free_balance, value);
+		<Module<T>>::snapshot_account(&A::get(), who);
+		<FreeBalance<T>>::insert(&A::get(), who, free_balance - free_slash);
+
+		let remaining_slash = value - free_slash;
+		let reserved_slash = Self::take_untagged_reserve(who, remaining_slash);
+		<Module<T>>::reap_if_dust(&A::get(), who);
+		(NegativeImbalance::new(free_slash + reserved_slash), remaining_slash - reserved_slash)
+	}
+
+	fn deposit_into_existing(
+		who: &T::AccountId,
+		value: Self::Balance,
+	) -> result::Result<Self::PositiveImbalance, DispatchError> {
+		if Self::total_balance(who).is_zero() {
+			return Err(Error::<T>::AccountNotExist.into());
+		}
+		<Module<T>>::ensure_not_frozen(&A::get(), who)?;
+		let free_new = Self::free_balance(who).checked_add(&value).ok_or(Error::<T>::OverFlowHappens)?;
+		<Module<T>>::snapshot_account(&A::get(), who);
+		<FreeBalance<T>>::insert(&A::get(), who, free_new);
+		Ok(PositiveImbalance::new(value))
+	}
+
+	fn deposit_creating(who: &T::AccountId, value: Self::Balance) -> Self::PositiveImbalance {
+		if <Module<T>>::ensure_can_create(&A::get(), who, value).is_err() || <Module<T>>::ensure_not_frozen(&A::get(), who).is_err() {
+			return PositiveImbalance::zero();
+		}
+		let free_old = Self::free_balance(who);
+		let free_new = free_old.saturating_add(value);
+		<Module<T>>::snapshot_account(&A::get(), who);
+		<FreeBalance<T>>::insert(&A::get(), who, free_new);
+		PositiveImbalance::new(free_new - free_old)
+	}
+
+	fn withdraw(
+		who: &T::AccountId,
+		value: Self::Balance,
+		reasons: WithdrawReasons,
+		liveness: ExistenceRequirement,
+	) -> result::Result<Self::NegativeImbalance, DispatchError> {
+		<Module<T>>::ensure_not_frozen(&A::get(), who)?;
+		let free_new = Self::free_balance(who).checked_sub(&value).ok_or(Error::<T>::AmountTooLow)?;
+		Self::ensure_keep_alive(who, value, liveness)?;
+		Self::ensure_can_withdraw(who, value, reasons, free_new)?;
+		<Module<T>>::snapshot_account(&A::get(), who);
+		<FreeBalance<T>>::insert(&A::get(), who, free_new);
+		<Module<T>>::reap_if_dust(&A::get(), who);
+		Ok(NegativeImbalance::new(value))
+	}
+
+	fn make_free_balance_be(
+		who: &T::AccountId,
+		balance: Self::Balance,
+	) -> SignedImbalance<Self::Balance, Self::PositiveImbalance> {
+		let original = Self::free_balance(who);
+		let imbalance = if original <= balance {
+			SignedImbalance::Positive(PositiveImbalance::new(balance - original))
+		} else {
+			SignedImbalance::Negative(NegativeImbalance::new(original - balance))
+		};
+		<Module<T>>::snapshot_account(&A::get(), who);
+		<FreeBalance<T>>::insert(&A::get(), who, balance);
+		<Module<T>>::reap_if_dust(&A::get(), who);
+		imbalance
+	}
+}
+
+impl<T: Trait, A: Get<T::AssetId>> ReservableCurrency<T::AccountId> for AssetCurrency<T, A> {
+	fn can_reserve(who: &T::AccountId, value: Self::Balance) -> bool {
+		Self::free_balance(who)
+			.checked_sub(&value)
+			.map_or(false, |new_balance| {
+				Self::ensure_can_withdraw(who, value, WithdrawReason::Reserve.into(), new_balance).is_ok()
+			})
+	}
+
+	fn slash_reserved(who: &T::AccountId, value: Self::Balance) -> (Self::NegativeImbalance, Self::Balance) {
+		let actual = Self::take_untagged_reserve(who, value);
+		<Module<T>>::reap_if_dust(&A::get(), who);
+		(NegativeImbalance::new(actual), value - actual)
+	}
+
+	fn reserved_balance(who: &T::AccountId) -> Self::Balance {
+		<Module<T>>::reserved_balance_of(&A::get(), who)
+	}
+
+	fn reserve(who: &T::AccountId, value: Self::Balance) -> DispatchResult {
+		if value.is_zero() {
+			return Ok(());
+		}
+		<Module<T>>::do_reserve(&A::get(), who, &T::ReserveIdentifier::default(), value)
+	}
+
+	fn unreserve(who: &T::AccountId, value: Self::Balance) -> Self::Balance {
+		let actual = cmp::min(Self::untagged_reserve_of(who), value);
+		if !actual.is_zero() {
+			// fails while the asset is paused or the account frozen, or on overflow of the free balance,
+			// nothing is unreserved then and the whole of `value` is reported back as not unreserved
+			if <Module<T>>::do_unreserve(&A::get(), who, &T::ReserveIdentifier::default(), actual).is_err() {
+				return value;
+			}
+		}
+		value - actual
+	}
+
+	fn repatriate_reserved(
+		slashed: &T::AccountId,
+		beneficiary: &T::AccountId,
+		value: Self::Balance,
+	) -> result::Result<Self::Balance, DispatchError> {
+		let actual = cmp::min(Self::untagged_reserve_of(slashed), value);
+		if !actual.is_zero() {
+			<Module<T>>::do_repatriate_reserved(&A::get(), slashed, &T::ReserveIdentifier::default(), beneficiary, actual)?;
+		}
+		Ok(value - actual)
+	}
+}
+
+impl<T: Trait, A: Get<T::AssetId>> LockableCurrency<T::AccountId> for AssetCurrency<T, A> {
+	type Moment = T::BlockNumber;
+
+	fn set_lock(
+		id: LockIdentifier,
+		who: &T::AccountId,
+		amount: Self::Balance,
+		until: T::BlockNumber,
+		reasons: WithdrawReasons,
+	) {
+		let now = <system::Module<T>>::block_number();
+		let mut new_lock = Some(BalanceLock { id, amount, until, reasons });
+		let mut locks = <Module<T>>::locks(&A::get(), who)
+			.into_iter()
+			.filter_map(|lock| {
+				if lock.id == id {
+					new_lock.take()
+				} else if lock.until > now {
+					Some(lock)
+				} else {
+					None
+				}
+			})
+			.collect::<Vec<_>>();
+		if let Some(lock) = new_lock {
+			locks.push(lock)
+		}
+		<Locks<T>>::insert(&A::get(), who, locks);
+	}
+
+	fn extend_lock(
+		id: LockIdentifier,
+		who: &T::AccountId,
+		amount: Self::Balance,
+		until: T::BlockNumber,
+		reasons: WithdrawReasons,
+	) {
+		let now = <system::Module<T>>::block_number();
+		let mut new_lock = Some(BalanceLock { id, amount, until, reasons });
+		let mut locks = <Module<T>>::locks(&A::get(), who)
+			.into_iter()
+			.filter_map(|lock| {
+				if lock.id == id {
+					new_lock.take().map(|nl| BalanceLock {
+						id: lock.id,
+						amount: lock.amount.max(nl.amount),
+						until: lock.until.max(nl.until),
+						reasons: lock.reasons | nl.reasons,
+					})
+				} else if lock.until > now {
+					Some(lock)
+				} else {
+					None
+				}
+			})
+			.collect::<Vec<_>>();
+		if let Some(lock) = new_lock {
+			locks.push(lock)
+		}
+		<Locks<T>>::insert(&A::get(), who, locks);
+	}
+
+	fn remove_lock(id: LockIdentifier, who: &T::AccountId) {
+		let now = <system::Module<T>>::block_number();
+		let locks = <Module<T>>::locks(&A::get(), who)
+			.into_iter()
+			.filter(|lock| lock.id != id && lock.until > now)
+			.collect::<Vec<_>>();
+		if locks.is_empty() {
+			<Locks<T>>::remove(&A::get(), who);
+		} else {
+			<Locks<T>>::insert(&A::get(), who, locks);
+		}
+	}
+}
@@ -1,23 +1,39 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 ///payment token module to handle
-/// token issue
-/// token transfer
-/// token reserve and unserve
+/// assets creation, each asset with its own owner, supply and balances
+/// token issue (once, at genesis), mint and burn
+/// ownership transfer and roles granted by the owner
+/// pause of an asset and freeze of an account
+/// token transfer, one by one or in batches, and airdrops
+/// vesting schedules locking part of the free balance
+/// token reserve and unserve under purpose tags, slash and repatriate reserves
+/// metadata of an asset (name, symbol, decimals)
+/// snapshots of balances and total supply, recorded lazily on the first change after each snapshot
+/// payment streams paying a payee a fixed amount per block out of a reserve of the payer
+/// minimum balance of an asset, accounts falling below it are reaped
+/// allowances set by the owner, or by anyone with a permit the owner signed off-chain
 /// enquery and set account balance and son on
-use frame_support::{decl_error, decl_event, decl_module, decl_storage, dispatch::DispatchResult,
+use frame_support::{decl_error, decl_event, decl_module, decl_storage, dispatch::{DispatchError, DispatchResult},
 	Parameter, ensure,
+	traits::{Get, LockIdentifier, WithdrawReason, WithdrawReasons},
+	weights::{ClassifyDispatch, DispatchClass, PaysFee, WeighData, Weight},
 };
 use sp_runtime::{
 	traits::{
-		Member, MaybeSerializeDeserialize, Zero
-	}
+		Convert, IdentifyAccount, Member, MaybeSerializeDeserialize, One, Saturating, Verify, Zero
+	},
+	RuntimeDebug,
 };
 use sp_runtime::traits::{CheckedAdd, CheckedSub, CheckedDiv, CheckedMul, Hash};
 use sp_arithmetic::traits::SimpleArithmetic;
 use codec::{Codec, Encode, Decode};
+use sp_std::{collections::btree_map::BTreeMap, prelude::*};
 use system::ensure_signed;
 
+mod currency;
+pub use currency::{AssetCurrency, NegativeImbalance, PositiveImbalance};
+
 #[cfg(all(feature = "std", test))]
 mod tests;
 
@@ -28,15 +44,230 @@ mod mock;
 pub trait Trait: system::Trait {
 	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
 	type Balance: Parameter + Member + Codec + Default + Copy + MaybeSerializeDeserialize + PartialOrd + SimpleArithmetic;
+	/// Identifier of an asset managed by this pallet.
+	type AssetId: Parameter + Member + Codec + Default + Copy + MaybeSerializeDeserialize + SimpleArithmetic;
+	/// Convert a block number into a balance, used to compute how much of a vesting schedule is unlocked.
+	type BlockNumberToBalance: Convert<Self::BlockNumber, Self::Balance>;
+	/// Tag of a reserve, telling apart what funds are held for. The default value is the untagged reserve.
+	type ReserveIdentifier: Parameter + Member + Codec + Default + Copy + MaybeSerializeDeserialize;
+	/// Told about the dust burned when an account is reaped.
+	type DustHandler: OnDustRemoval<Self::AssetId, Self::AccountId, Self::Balance>;
+	/// Tag of the reserve funds committed to payment streams are held in.
+	type StreamReserveId: Get<Self::ReserveIdentifier>;
+	/// Off-chain signature owners sign permits with, the runtime's `MultiSignature`.
+	type Signature: Parameter + Verify<Signer = Self::Signer>;
+	/// Key a permit signature is checked against, identifying the owner's account.
+	type Signer: IdentifyAccount<AccountId = Self::AccountId>;
+}
+
+/// Handler for the dust left in an account when it is reaped.
+pub trait OnDustRemoval<AssetId, AccountId, Balance> {
+	/// `amount` of `asset_id` was left in the reaped account `who`. It is already burned, so a handler
+	/// keeping the dust has to mint it again wherever it should go.
+	fn on_dust_removal(asset_id: &AssetId, who: &AccountId, amount: Balance);
+}
+
+impl<AssetId, AccountId, Balance> OnDustRemoval<AssetId, AccountId, Balance> for () {
+	fn on_dust_removal(_: &AssetId, _: &AccountId, _: Balance) {}
+}
+
+/// Struct to encode the vesting schedule of an individual account.
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct VestingSchedule<Balance, BlockNumber> {
+	/// Locked amount at the start of the schedule.
+	pub locked: Balance,
+	/// Amount that gets unlocked every block after `starting_block`.
+	pub per_block: Balance,
+	/// Block from which unlocking is counted.
+	pub starting_block: BlockNumber,
+	/// Nothing is unlocked before this block.
+	pub cliff: BlockNumber,
+}
+
+impl<Balance: SimpleArithmetic + Copy, BlockNumber: SimpleArithmetic + Copy> VestingSchedule<Balance, BlockNumber> {
+	/// Amount still locked at block `n`.
+	pub fn locked_at<BlockNumberToBalance: Convert<BlockNumber, Balance>>(&self, n: BlockNumber) -> Balance {
+		if n < self.cliff {
+			return self.locked;
+		}
+
+		let vested_block_count = BlockNumberToBalance::convert(n.saturating_sub(self.starting_block));
+		match vested_block_count.checked_mul(&self.per_block) {
+			Some(unlocked) => self.locked.saturating_sub(unlocked),
+			None => Zero::zero(),
+		}
+	}
+}
+
+/// Longest name or symbol an asset may have, in bytes.
+pub const MAX_METADATA_LENGTH: usize = 32;
+
+/// Describes how an asset is displayed.
+#[derive(Encode, Decode, Clone, Default, PartialEq, Eq, RuntimeDebug)]
+pub struct AssetMetadata {
+	/// Full name, utf8 encoded.
+	pub name: Vec<u8>,
+	/// Ticker symbol, utf8 encoded.
+	pub symbol: Vec<u8>,
+	/// Number of decimals of one whole unit of the asset.
+	pub decimals: u8,
+}
+
+/// Permission the owner of an asset can grant to other accounts. The owner implicitly holds every role.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum Role {
+	/// May mint and burn.
+	Minter,
+	/// May freeze and thaw accounts.
+	Freezer,
+	/// May pause and unpause the asset.
+	Pauser,
+}
+
+pub type StreamId = u64;
+
+/// Prefix of a signed permit payload, so that a permit signature can't pass for anything else.
+pub const PERMIT_CONTEXT: &[u8] = b"payment_token:permit";
+
+/// Id of a snapshot of an asset. Snapshots of an asset are numbered from 1.
+pub type SnapshotId = u32;
+
+/// Payment of `rate_per_block` from `payer` to `payee` for every block from `start` to `end`.
+/// The whole amount is reserved from the payer when the stream is created, and what has
+/// accrued so far is only worked out when the stream is touched.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Stream<AccountId, AssetId, Balance, BlockNumber> {
+	pub payer: AccountId,
+	pub payee: AccountId,
+	pub asset_id: AssetId,
+	pub rate_per_block: Balance,
+	pub start: BlockNumber,
+	pub end: BlockNumber,
+	/// Amount the payee already withdrew.
+	pub withdrawn: Balance,
+}
+
+type StreamOf<T> = Stream<<T as system::Trait>::AccountId, <T as Trait>::AssetId, <T as Trait>::Balance, <T as system::Trait>::BlockNumber>;
+
+/// Longest memo a transfer may carry, in bytes.
+pub const MAX_MEMO_LENGTH: usize = 128;
+
+/// Weight of a call paying out to a list of recipients: a base weight plus a weight per recipient.
+pub struct PerRecipientWeight(Weight, Weight);
+
+impl<AssetId, Recipient> WeighData<(&AssetId, &Vec<Recipient>)> for PerRecipientWeight {
+	fn weigh_data(&self, (_, recipients): (&AssetId, &Vec<Recipient>)) -> Weight {
+		self.1.saturating_mul(recipients.len() as Weight).saturating_add(self.0)
+	}
+}
+
+impl<AssetId, Recipient> ClassifyDispatch<(&AssetId, &Vec<Recipient>)> for PerRecipientWeight {
+	fn classify_dispatch(&self, _: (&AssetId, &Vec<Recipient>)) -> DispatchClass {
+		DispatchClass::Normal
+	}
+}
+
+impl PaysFee for PerRecipientWeight {
+	fn pays_fee(&self) -> bool {
+		true
+	}
+}
+
+/// A lock put on an account's free balance by another pallet through `LockableCurrency`.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct BalanceLock<Balance, BlockNumber> {
+	pub id: LockIdentifier,
+	pub amount: Balance,
+	pub until: BlockNumber,
+	pub reasons: WithdrawReasons,
 }
 
 // This pallet's storage items.
 decl_storage! {
 	trait Store for Module<T: Trait> as PaymentToken {
-		Owner get(token_owner) config(): T::AccountId;
-		TotalSupply get(total_supply) config(): T::Balance;
-		Balance get(free_balance_of): map hasher(blake2_256) T::AccountId => T::Balance;
-		Reserved get (reserved_balance_of): map hasher(blake2_256) T::AccountId => T::Balance;
+		NextAssetId get(next_asset_id): T::AssetId;
+		Owner get(token_owner): map hasher(blake2_256) T::AssetId => Option<T::AccountId>;
+		/// Roles granted by the owner of an asset, keyed by (role, account).
+		Roles get(has_role): double_map hasher(blake2_256) T::AssetId, blake2_256((Role, T::AccountId)) => bool;
+		/// Assets whose transfers and reserves are halted.
+		PausedAssets get(is_paused): map hasher(blake2_256) T::AssetId => bool;
+		/// Accounts that can neither move nor receive an asset.
+		FrozenAccounts get(is_frozen): double_map hasher(blake2_256) T::AssetId, blake2_256(T::AccountId) => bool;
+		TotalSupply get(total_supply): map hasher(blake2_256) T::AssetId => T::Balance;
+		Metadata get(metadata): map hasher(blake2_256) T::AssetId => AssetMetadata;
+		/// Smallest balance an account may hold; once both its free balance and its reserve are below it the account is reaped.
+		MinimumBalance get(minimum_balance): map hasher(blake2_256) T::AssetId => T::Balance;
+		Balance get(free_balance_of): double_map hasher(blake2_256) T::AssetId, blake2_256(T::AccountId) => T::Balance;
+		/// Sum of all the reserves of an account.
+		Reserved get (reserved_balance_of): double_map hasher(blake2_256) T::AssetId, blake2_256(T::AccountId) => T::Balance;
+		/// Reserve of an account under a given tag, keyed by (account, reserve id).
+		NamedReserves get(named_reserve_of): double_map hasher(blake2_256) T::AssetId, blake2_256((T::AccountId, T::ReserveIdentifier)) => T::Balance;
+		/// Amount a spender is still allowed to move out of an owner's free balance, keyed by (owner, spender).
+		Allowances get(allowance): double_map hasher(blake2_256) T::AssetId, blake2_256((T::AccountId, T::AccountId)) => T::Balance;
+		/// Number of permits used by an owner, to be signed into the next one so none can be replayed.
+		PermitNonces get(permit_nonce): map hasher(blake2_256) T::AccountId => u64;
+		/// Vesting schedule locking part of an account's free balance.
+		Vesting get(vesting): double_map hasher(blake2_256) T::AssetId, blake2_256(T::AccountId) => Option<VestingSchedule<T::Balance, T::BlockNumber>>;
+		/// Locks on an account's free balance, set through `LockableCurrency`.
+		Locks get(locks): double_map hasher(blake2_256) T::AssetId, blake2_256(T::AccountId) => Vec<BalanceLock<T::Balance, T::BlockNumber>>;
+		/// Id of the latest snapshot of an asset, 0 while none was taken.
+		CurrentSnapshot get(current_snapshot): map hasher(blake2_256) T::AssetId => SnapshotId;
+		/// Number of snapshot records of an account, see `AccountSnapshots`.
+		AccountSnapshotCount get(account_snapshot_count): double_map hasher(blake2_256) T::AssetId, blake2_256(T::AccountId) => u32;
+		/// Total balance an account had at a snapshot it changed after, keyed by (account, record index).
+		/// Records of an account are by increasing snapshot id, so that they can be binary searched.
+		AccountSnapshots get(account_snapshot): double_map hasher(blake2_256) T::AssetId, blake2_256((T::AccountId, u32)) => (SnapshotId, T::Balance);
+		/// Number of snapshot records of the total supply, see `SupplySnapshots`.
+		SupplySnapshotCount get(supply_snapshot_count): map hasher(blake2_256) T::AssetId => u32;
+		/// Total supply at a snapshot it changed after, keyed by record index, by increasing snapshot id.
+		SupplySnapshots get(supply_snapshot): double_map hasher(blake2_256) T::AssetId, blake2_256(u32) => (SnapshotId, T::Balance);
+		NextStreamId get(next_stream_id): StreamId;
+		/// Payment streams not yet fully paid out nor cancelled.
+		Streams get(stream): map hasher(blake2_256) StreamId => Option<StreamOf<T>>;
+	}
+	add_extra_genesis {
+		/// (asset id, owner, total supply) of the assets existing at genesis.
+		config(assets): Vec<(T::AssetId, T::AccountId, T::Balance)>;
+		/// (asset id, account, locked, per block, starting block, cliff) of the vesting schedules existing at genesis.
+		config(vesting): Vec<(T::AssetId, T::AccountId, T::Balance, T::Balance, T::BlockNumber, T::BlockNumber)>;
+		/// (asset id, minimum balance) of the assets existing at genesis.
+		config(minimum_balances): Vec<(T::AssetId, T::Balance)>;
+		/// (asset id, name, symbol, decimals) of the assets existing at genesis.
+		config(metadata): Vec<(T::AssetId, Vec<u8>, Vec<u8>, u8)>;
+		// the whole initial supply of each asset is issued to its owner once, at genesis
+		build(|config: &GenesisConfig<T>| {
+			for (asset_id, owner, total_supply) in config.assets.iter() {
+				<Owner<T>>::insert(asset_id, owner);
+				<TotalSupply<T>>::insert(asset_id, total_supply);
+				<Balance<T>>::insert(asset_id, owner, total_supply);
+
+				if *asset_id >= <NextAssetId<T>>::get() {
+					<NextAssetId<T>>::put(*asset_id + One::one());
+				}
+			}
+
+			for (asset_id, who, locked, per_block, starting_block, cliff) in config.vesting.iter() {
+				<Vesting<T>>::insert(asset_id, who, VestingSchedule {
+					locked: *locked,
+					per_block: *per_block,
+					starting_block: *starting_block,
+					cliff: *cliff,
+				});
+			}
+
+			for (asset_id, minimum_balance) in config.minimum_balances.iter() {
+				<MinimumBalance<T>>::insert(asset_id, minimum_balance);
+			}
+
+			for (asset_id, name, symbol, decimals) in config.metadata.iter() {
+				assert!(name.len() <= MAX_METADATA_LENGTH && symbol.len() <= MAX_METADATA_LENGTH, "asset metadata too long");
+				<Metadata<T>>::insert(asset_id, AssetMetadata {
+					name: name.clone(),
+					symbol: symbol.clone(),
+					decimals: *decimals,
+				});
+			}
+		});
 	}
 }
 
@@ -44,127 +275,992 @@ decl_storage! {
 decl_module! {
 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
 		fn deposit_event() = default;
-		
-		fn issue_token(origin) -> DispatchResult {
+
+		/// Create a new asset owned by the sender, issuing `total_supply` to the sender. Accounts left with
+		/// less than `minimum_balance` of it are reaped.
+		fn create_asset(origin, total_supply: T::Balance, minimum_balance: T::Balance) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
-			ensure!(sender == Self::token_owner(), Error::<T>::OnlyOwnerCanOperate);
-			
-			let total_suppy = Self::total_supply();
-			<Balance<T>>::insert(&sender, total_suppy);
+			ensure!(total_supply >= minimum_balance, Error::<T>::BelowMinimumBalance);
+
+			let asset_id = Self::next_asset_id();
+			let next_asset_id = asset_id.checked_add(&One::one()).ok_or(Error::<T>::OverFlowHappens)?;
 
-			Self::deposit_event(RawEvent::TokenIssued(sender.clone(), total_suppy));
+			<Owner<T>>::insert(&asset_id, &sender);
+			<TotalSupply<T>>::insert(&asset_id, total_supply);
+			<Balance<T>>::insert(&asset_id, &sender, total_supply);
+			<MinimumBalance<T>>::insert(&asset_id, minimum_balance);
+			<NextAssetId<T>>::put(next_asset_id);
 
+			Self::deposit_event(RawEvent::AssetCreated(asset_id, sender, total_supply));
+			Self::deposit_event(RawEvent::MinimumBalanceSet(asset_id, minimum_balance));
 			Ok(())
 		}
 
 		//Note that this will not maintain the original total supply of tokens
-		fn set_balance(origin, account: T::AccountId, new_free: T::Balance, new_reserve: T::Balance) -> DispatchResult {
+		fn set_balance(origin, asset_id: T::AssetId, account: T::AccountId, new_free: T::Balance, new_reserve: T::Balance) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
-			ensure!(sender == Self::token_owner(), Error::<T>::OnlyOwnerCanOperate);
+			Self::ensure_owner(&asset_id, &sender)?;
 
-			let old_total = Self::free_balance_of(&account) + Self::reserved_balance_of(&account);
+			//the reserve set here is the anonymous (default id) one, tagged reserves are left untouched
+			let reserved_old = Self::reserved_balance_of(&asset_id, &account);
+			let default_id = T::ReserveIdentifier::default();
+			let tagged_reserved = reserved_old - Self::named_reserve_of(&asset_id, &(account.clone(), default_id));
+			ensure!(new_reserve >= tagged_reserved, Error::<T>::AmountTooLow);
+
+			let old_total = Self::free_balance_of(&asset_id, &account) + reserved_old;
 			let new_total = new_free + new_reserve;
+			Self::snapshot_account(&asset_id, &account);
+			Self::snapshot_supply(&asset_id);
 			if old_total <= new_total {
-				<TotalSupply<T>>::mutate(|v| *v+= new_total - old_total);
+				<TotalSupply<T>>::mutate(&asset_id, |v| *v+= new_total - old_total);
 			} else {
-				<TotalSupply<T>>::mutate(|v| *v-= old_total - new_total);
+				<TotalSupply<T>>::mutate(&asset_id, |v| *v-= old_total - new_total);
 			}
 
-			<Balance<T>>::insert(&account, new_free);
-			<Reserved<T>>::insert(&account, new_reserve);
+			<Balance<T>>::insert(&asset_id, &account, new_free);
+			<Reserved<T>>::insert(&asset_id, &account, new_reserve);
+			Self::set_named_reserve(&asset_id, &account, &default_id, new_reserve - tagged_reserved);
+
+			Self::deposit_event(RawEvent::BalanceSet(asset_id, account.clone(), new_free, new_reserve));
+			Self::reap_if_dust(&asset_id, &account);
+
+			Ok(())
+		}
+
+		fn set_metadata(origin, asset_id: T::AssetId, name: Vec<u8>, symbol: Vec<u8>, decimals: u8) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_owner(&asset_id, &sender)?;
+			ensure!(name.len() <= MAX_METADATA_LENGTH && symbol.len() <= MAX_METADATA_LENGTH, Error::<T>::MetadataTooLong);
+
+			<Metadata<T>>::insert(&asset_id, AssetMetadata {
+				name: name.clone(),
+				symbol: symbol.clone(),
+				decimals,
+			});
 
-			Self::deposit_event(RawEvent::BalanceSet(account, new_free, new_reserve));
-			
+			Self::deposit_event(RawEvent::MetadataSet(asset_id, name, symbol, decimals));
 			Ok(())
 		}
 
-		fn transfer_from(origin, to: T::AccountId, amount: T::Balance)  -> DispatchResult {
+		/// Set the smallest free balance an account may hold. Existing accounts below it are reaped
+		/// the next time their balance goes down.
+		fn set_minimum_balance(origin, asset_id: T::AssetId, minimum_balance: T::Balance) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_owner(&asset_id, &sender)?;
+
+			<MinimumBalance<T>>::insert(&asset_id, minimum_balance);
+
+			Self::deposit_event(RawEvent::MinimumBalanceSet(asset_id, minimum_balance));
+			Ok(())
+		}
+
+		fn transfer_ownership(origin, asset_id: T::AssetId, new_owner: T::AccountId) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_owner(&asset_id, &sender)?;
+
+			<Owner<T>>::insert(&asset_id, &new_owner);
+
+			Self::deposit_event(RawEvent::OwnershipTransferred(asset_id, sender, new_owner));
+			Ok(())
+		}
+
+		fn grant_role(origin, asset_id: T::AssetId, role: Role, who: T::AccountId) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_owner(&asset_id, &sender)?;
+
+			<Roles<T>>::insert(&asset_id, &(role, who.clone()), true);
+
+			Self::deposit_event(RawEvent::RoleGranted(asset_id, role, who));
+			Ok(())
+		}
+
+		fn revoke_role(origin, asset_id: T::AssetId, role: Role, who: T::AccountId) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_owner(&asset_id, &sender)?;
+
+			<Roles<T>>::remove(&asset_id, &(role, who.clone()));
+
+			Self::deposit_event(RawEvent::RoleRevoked(asset_id, role, who));
+			Ok(())
+		}
+
+		fn pause(origin, asset_id: T::AssetId) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_role(&asset_id, Role::Pauser, &sender)?;
+
+			<PausedAssets<T>>::insert(&asset_id, true);
+
+			Self::deposit_event(RawEvent::Paused(asset_id));
+			Ok(())
+		}
+
+		fn unpause(origin, asset_id: T::AssetId) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_role(&asset_id, Role::Pauser, &sender)?;
+
+			<PausedAssets<T>>::remove(&asset_id);
+
+			Self::deposit_event(RawEvent::Unpaused(asset_id));
+			Ok(())
+		}
+
+		fn freeze_account(origin, asset_id: T::AssetId, account: T::AccountId) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_role(&asset_id, Role::Freezer, &sender)?;
+
+			<FrozenAccounts<T>>::insert(&asset_id, &account, true);
+
+			Self::deposit_event(RawEvent::Frozen(asset_id, account));
+			Ok(())
+		}
+
+		fn thaw_account(origin, asset_id: T::AssetId, account: T::AccountId) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_role(&asset_id, Role::Freezer, &sender)?;
+
+			<FrozenAccounts<T>>::remove(&asset_id, &account);
+
+			Self::deposit_event(RawEvent::Thawed(asset_id, account));
+			Ok(())
+		}
+
+		fn mint(origin, asset_id: T::AssetId, to: T::AccountId, amount: T::Balance) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_role(&asset_id, Role::Minter, &sender)?;
+
+			if amount.is_zero() {return Ok(())};
+			Self::do_mint(&asset_id, &to, amount)
+		}
+
+		fn burn(origin, asset_id: T::AssetId, from: T::AccountId, amount: T::Balance) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_role(&asset_id, Role::Minter, &sender)?;
+
+			if amount.is_zero() {return Ok(())};
+			Self::do_burn(&asset_id, &from, amount)
+		}
+
+		/// Burn `amount` of the sender's own free balance.
+		fn burn_own(origin, asset_id: T::AssetId, amount: T::Balance) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			if amount.is_zero() {return Ok(())};
+			Self::do_burn(&asset_id, &sender, amount)
+		}
+
+		fn transfer_from(origin, asset_id: T::AssetId, to: T::AccountId, amount: T::Balance)  -> DispatchResult {
 			let sender = ensure_signed(origin)?;
 			if sender == to || amount.is_zero() {
 				return Ok(());
 			};
 
-			Self::transfer(sender, to, amount)?;
+			Self::transfer(asset_id, sender, to, amount)?;
 			Ok(())
 		}
 
-		fn reserve_token(origin, account: T::AccountId, amount: T::Balance) -> DispatchResult {
+		/// Take a snapshot of the balances and total supply of the asset.
+		fn snapshot(origin, asset_id: T::AssetId) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
-			ensure!(sender == Self::token_owner(), Error::<T>::OnlyOwnerCanOperate);
+			Self::ensure_owner(&asset_id, &sender)?;
 
-			if amount.is_zero() {return Ok(())};
-			let free_old = Self::free_balance_of(&account);
-			ensure!(free_old >= amount, Error::<T>::AmountTooLow);
-			let free_new = free_old.checked_sub(&amount).ok_or(Error::<T>::UnderFlowHappens)?;
-			let reserved_old = Self::reserved_balance_of(&account);
-			let reserved_new = reserved_old.checked_add(&amount).ok_or(Error::<T>::OverFlowHappens)?;
+			Self::do_snapshot(&asset_id)?;
+			Ok(())
+		}
+
+		/// Pay `payee` `rate_per_block` for every block from `start` to `end`, reserving the whole
+		/// amount from the sender up front.
+		fn create_stream(
+			origin,
+			asset_id: T::AssetId,
+			payee: T::AccountId,
+			rate_per_block: T::Balance,
+			start: T::BlockNumber,
+			end: T::BlockNumber
+		) -> DispatchResult {
+			let payer = ensure_signed(origin)?;
+			ensure!(payer != payee, Error::<T>::TransferToSelf);
+			ensure!(!rate_per_block.is_zero(), Error::<T>::AmountTooLow);
+			ensure!(start >= <system::Module<T>>::block_number() && end > start, Error::<T>::InvalidStreamPeriod);
 
-			<Balance<T>>::insert(&account, free_new);
-			<Reserved<T>>::insert(&account, reserved_new);
+			let total = T::BlockNumberToBalance::convert(end - start)
+				.checked_mul(&rate_per_block).ok_or(Error::<T>::OverFlowHappens)?;
+			let stream_id = Self::next_stream_id();
+			let next_stream_id = stream_id.checked_add(1).ok_or(Error::<T>::OverFlowHappens)?;
+			Self::do_reserve(&asset_id, &payer, &T::StreamReserveId::get(), total)?;
 
+			<Streams<T>>::insert(stream_id, Stream {
+				payer: payer.clone(),
+				payee: payee.clone(),
+				asset_id,
+				rate_per_block,
+				start,
+				end,
+				withdrawn: Zero::zero(),
+			});
+			NextStreamId::put(next_stream_id);
+
+			Self::deposit_event(RawEvent::StreamCreated(stream_id, asset_id, payer, payee, total));
 			Ok(())
 		}
 
-		fn unreserve_token(origin, account: T::AccountId, amount: T::Balance) -> DispatchResult {
+		/// Pay out to the payee everything the stream accrued and it hasn't withdrawn yet.
+		fn withdraw_from_stream(origin, stream_id: StreamId) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
-			ensure!(sender == Self::token_owner(), Error::<T>::OnlyOwnerCanOperate);
+			let mut stream = Self::stream(stream_id).ok_or(Error::<T>::StreamNotExist)?;
+			ensure!(sender == stream.payee, Error::<T>::NotStreamPayee);
 
-			if amount.is_zero() {return Ok(())};
-			let reserved_old = Self::reserved_balance_of(&account);
-			ensure!(reserved_old >= amount, Error::<T>::AmountTooLow);
-			let reserved_new = reserved_old.checked_sub(&amount).ok_or(Error::<T>::UnderFlowHappens)?;
-			let free_old = Self::free_balance_of(&account);
-			let free_new = free_old.checked_add(&amount).ok_or(Error::<T>::OverFlowHappens)?;
+			let now = <system::Module<T>>::block_number();
+			let available = Self::stream_accrued(&stream, now) - stream.withdrawn;
+			if !available.is_zero() {
+				Self::do_repatriate_reserved(&stream.asset_id, &stream.payer, &T::StreamReserveId::get(), &stream.payee, available)?;
+			}
+
+			if now >= stream.end {
+				<Streams<T>>::remove(stream_id);
+			} else {
+				stream.withdrawn += available;
+				<Streams<T>>::insert(stream_id, stream);
+			}
+
+			Self::deposit_event(RawEvent::StreamWithdrawn(stream_id, available));
+			Ok(())
+		}
+
+		/// Stop the stream, settling pro rata: the payee gets what accrued so far and the rest goes back to the payer.
+		fn cancel_stream(origin, stream_id: StreamId) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let stream = Self::stream(stream_id).ok_or(Error::<T>::StreamNotExist)?;
+			ensure!(sender == stream.payer || sender == stream.payee, Error::<T>::NotStreamParty);
+
+			let accrued = Self::stream_accrued(&stream, <system::Module<T>>::block_number());
+			let to_payee = accrued - stream.withdrawn;
+			let to_payer = Self::stream_total(&stream) - accrued;
+			// either both sides are paid or neither is, so a failed cancel leaves nothing to withdraw twice
+			Self::do_settle_reserved(&stream.asset_id, &stream.payer, &T::StreamReserveId::get(), &stream.payee, to_payee, to_payer)?;
+			<Streams<T>>::remove(stream_id);
+
+			Self::deposit_event(RawEvent::StreamCancelled(stream_id, to_payee, to_payer));
+			Ok(())
+		}
+
+		/// Transfer with a payment reference, such as an order id, carried in `TokenTransferredWithMemo`
+		/// instead of `TokenTransferred`.
+		fn transfer_with_memo(origin, asset_id: T::AssetId, to: T::AccountId, amount: T::Balance, memo: Vec<u8>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(memo.len() <= MAX_MEMO_LENGTH, Error::<T>::MemoTooLong);
+			ensure!(sender != to, Error::<T>::TransferToSelf);
+			ensure!(!amount.is_zero(), Error::<T>::AmountTooLow);
+
+			Self::move_free_balance(&asset_id, &sender, &to, amount)?;
+
+			Self::deposit_event(RawEvent::TokenTransferredWithMemo(asset_id, sender.clone(), to, amount, memo));
+			Self::reap_if_dust(&asset_id, &sender);
+			Ok(())
+		}
+
+		/// Transfer to every `(recipient, amount)` of `transfers`. Either all of them go through or none does.
+		#[weight = PerRecipientWeight(10_000, 2_000)]
+		fn batch_transfer(origin, asset_id: T::AssetId, transfers: Vec<(T::AccountId, T::Balance)>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			Self::do_batch_transfer(&asset_id, &sender, transfers)
+		}
+
+		/// Mint to every `(recipient, amount)` of `recipients`. Either all of them are paid or none is.
+		#[weight = PerRecipientWeight(10_000, 2_000)]
+		fn airdrop(origin, asset_id: T::AssetId, recipients: Vec<(T::AccountId, T::Balance)>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_owner(&asset_id, &sender)?;
+
+			let (credits, total) = Self::ensure_can_create_all(&asset_id, &recipients)?;
+			Self::total_supply(&asset_id).checked_add(&total).ok_or(Error::<T>::OverFlowHappens)?;
+
+			//a recipient listed more than once is paid once, with the sum of its amounts
+			let count = credits.len() as u32;
+			for (to, amount) in credits.iter() {
+				Self::do_mint(&asset_id, to, *amount)?;
+			}
+
+			Self::deposit_event(RawEvent::Airdropped(asset_id, count, total));
+			Ok(())
+		}
+
+		/// Allow `spender` to move up to `amount` of the sender's free balance, replacing any previous allowance.
+		fn approve(origin, asset_id: T::AssetId, spender: T::AccountId, amount: T::Balance) -> DispatchResult {
+			let owner = ensure_signed(origin)?;
+
+			Self::set_allowance(&asset_id, &owner, &spender, amount);
+			Ok(())
+		}
+
+		/// Set the allowance of `spender` over the tokens of `owner` on behalf of the owner, who signed
+		/// the permit off-chain. Anyone may submit it until the deadline block.
+		fn permit(
+			origin,
+			asset_id: T::AssetId,
+			owner: T::AccountId,
+			spender: T::AccountId,
+			amount: T::Balance,
+			deadline: T::BlockNumber,
+			signature: T::Signature
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+			ensure!(<system::Module<T>>::block_number() <= deadline, Error::<T>::PermitExpired);
+
+			let nonce = Self::permit_nonce(&owner);
+			let payload = Self::permit_payload(&asset_id, &owner, &spender, amount, nonce, deadline);
+			ensure!(signature.verify(&payload[..], &owner), Error::<T>::InvalidSignature);
+
+			<PermitNonces<T>>::insert(&owner, nonce + 1);
+			Self::set_allowance(&asset_id, &owner, &spender, amount);
+			Ok(())
+		}
+
+		fn increase_allowance(origin, asset_id: T::AssetId, spender: T::AccountId, delta: T::Balance) -> DispatchResult {
+			let owner = ensure_signed(origin)?;
+
+			let allowance_new = Self::allowance(&asset_id, &(owner.clone(), spender.clone()))
+				.checked_add(&delta).ok_or(Error::<T>::OverFlowHappens)?;
+			Self::set_allowance(&asset_id, &owner, &spender, allowance_new);
+			Ok(())
+		}
+
+		fn decrease_allowance(origin, asset_id: T::AssetId, spender: T::AccountId, delta: T::Balance) -> DispatchResult {
+			let owner = ensure_signed(origin)?;
 
-			<Balance<T>>::insert(&account, free_new);
-			<Reserved<T>>::insert(&account, reserved_new);
+			let allowance_new = Self::allowance(&asset_id, &(owner.clone(), spender.clone()))
+				.checked_sub(&delta).ok_or(Error::<T>::AllowanceTooLow)?;
+			Self::set_allowance(&asset_id, &owner, &spender, allowance_new);
+			Ok(())
+		}
+
+		/// Move `amount` from `from` to `to` on behalf of `from`, spending the sender's allowance.
+		fn transfer_approved(origin, asset_id: T::AssetId, from: T::AccountId, to: T::AccountId, amount: T::Balance) -> DispatchResult {
+			let spender = ensure_signed(origin)?;
+			if from == to || amount.is_zero() {
+				return Ok(());
+			};
+
+			let allowance = Self::allowance(&asset_id, &(from.clone(), spender.clone()));
+			ensure!(allowance >= amount, Error::<T>::AllowanceTooLow);
+			let allowance_new = allowance.checked_sub(&amount).ok_or(Error::<T>::UnderFlowHappens)?;
+
+			Self::transfer(asset_id, from.clone(), to, amount)?;
+			Self::set_allowance(&asset_id, &from, &spender, allowance_new);
+			Ok(())
+		}
+
+		/// Unlock whatever the sender's vesting schedule has vested so far, removing the schedule once it is done.
+		fn vest(origin, asset_id: T::AssetId) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(<Vesting<T>>::exists(&asset_id, &sender), Error::<T>::NotVesting);
+			let locked = Self::vesting_locked(&asset_id, &sender);
+			if locked.is_zero() {
+				<Vesting<T>>::remove(&asset_id, &sender);
+				Self::deposit_event(RawEvent::VestingCompleted(asset_id, sender));
+			} else {
+				Self::deposit_event(RawEvent::VestingUpdated(asset_id, sender, locked));
+			}
 
 			Ok(())
 		}
 
+		fn reserve_token(origin, asset_id: T::AssetId, account: T::AccountId, id: T::ReserveIdentifier, amount: T::Balance) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_owner(&asset_id, &sender)?;
+
+			if amount.is_zero() {return Ok(())};
+			Self::do_reserve(&asset_id, &account, &id, amount)
+		}
+
+		fn unreserve_token(origin, asset_id: T::AssetId, account: T::AccountId, id: T::ReserveIdentifier, amount: T::Balance) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_owner(&asset_id, &sender)?;
+
+			if amount.is_zero() {return Ok(())};
+			Self::do_unreserve(&asset_id, &account, &id, amount)
+		}
+
+		/// Destroy `amount` out of the `id` reserve of `account`, shrinking the total supply alike.
+		fn slash_reserved(origin, asset_id: T::AssetId, account: T::AccountId, id: T::ReserveIdentifier, amount: T::Balance) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_owner(&asset_id, &sender)?;
+
+			if amount.is_zero() {return Ok(())};
+			Self::do_slash_reserved(&asset_id, &account, &id, amount)
+		}
+
+		/// Move `amount` out of the `id` reserve of `from` into the free balance of `to`.
+		fn repatriate_reserved(
+			origin,
+			asset_id: T::AssetId,
+			from: T::AccountId,
+			id: T::ReserveIdentifier,
+			to: T::AccountId,
+			amount: T::Balance
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_owner(&asset_id, &sender)?;
+
+			if amount.is_zero() {return Ok(())};
+			Self::do_repatriate_reserved(&asset_id, &from, &id, &to, amount)
+		}
 	}
 }
 
 impl<T: Trait> Module<T> {
-	fn transfer(from: T::AccountId, to: T::AccountId, amount: T::Balance) ->DispatchResult {
-		ensure!(<Balance<T>>::exists(&from), Error::<T>::AccountNotExist);
+	fn transfer(asset_id: T::AssetId, from: T::AccountId, to: T::AccountId, amount: T::Balance) ->DispatchResult {
+		Self::move_free_balance(&asset_id, &from, &to, amount)?;
 
-		let free_balance = Self::free_balance_of(&from);
+		Self::deposit_event(RawEvent::TokenTransferred(asset_id, from.clone(), to, amount));
+		Self::reap_if_dust(&asset_id, &from);
+		Ok(())
+	}
+
+	/// Move `amount` from the free balance of `from` to the one of `to`, without emitting any event
+	/// nor reaping `from`.
+	fn move_free_balance(asset_id: &T::AssetId, from: &T::AccountId, to: &T::AccountId, amount: T::Balance) -> DispatchResult {
+		ensure!(<Balance<T>>::exists(asset_id, from), Error::<T>::AccountNotExist);
+		Self::ensure_not_frozen(asset_id, from)?;
+		Self::ensure_not_frozen(asset_id, to)?;
+
+		let free_balance = Self::free_balance_of(asset_id, from);
 		ensure!(free_balance >= amount, Error::<T>::AmountTooLow);
-		
-		if <Balance<T>>::exists(&to) {
-			let old_balance = Self::free_balance_of(&to);
+		Self::ensure_can_withdraw(asset_id, from, WithdrawReason::Transfer.into(), free_balance - amount)?;
+		Self::ensure_can_create(asset_id, to, amount)?;
+
+		Self::snapshot_account(asset_id, from);
+		Self::snapshot_account(asset_id, to);
+		if <Balance<T>>::exists(asset_id, to) {
+			let old_balance = Self::free_balance_of(asset_id, to);
 			let new_balance = old_balance.checked_add(&amount).ok_or(Error::<T>::OverFlowHappens)?;
-			<Balance<T>>::insert(&to, new_balance);
+			<Balance<T>>::insert(asset_id, to, new_balance);
 		} else {
-			<Balance<T>>::insert(&to, amount);
+			<Balance<T>>::insert(asset_id, to, amount);
 		}
-		
+
 		let new_balance_from = free_balance.checked_sub(&amount).ok_or(Error::<T>::UnderFlowHappens)?;
-		<Balance<T>>::insert(&from, new_balance_from);
+		<Balance<T>>::insert(asset_id, from, new_balance_from);
+		Ok(())
+	}
+
+	/// Total amount `stream` pays over its whole period.
+	fn stream_total(stream: &StreamOf<T>) -> T::Balance {
+		T::BlockNumberToBalance::convert(stream.end - stream.start) * stream.rate_per_block
+	}
 
-		Self::deposit_event(RawEvent::TokenTransferred(from, to, amount));
+	/// Amount `stream` accrued to the payee by block `now`, withdrawn or not.
+	pub fn stream_accrued(stream: &StreamOf<T>, now: T::BlockNumber) -> T::Balance {
+		if now <= stream.start {
+			return Zero::zero();
+		}
+		let elapsed = now.min(stream.end) - stream.start;
+		T::BlockNumberToBalance::convert(elapsed) * stream.rate_per_block
+	}
+
+	/// Move each amount of `transfers` out of `from`'s free balance, checking everything first so that
+	/// either every transfer happens or none does.
+	fn do_batch_transfer(
+		asset_id: &T::AssetId,
+		from: &T::AccountId,
+		transfers: Vec<(T::AccountId, T::Balance)>,
+	) -> DispatchResult {
+		let transfers = transfers.into_iter()
+			.filter(|(to, amount)| to != from && !amount.is_zero())
+			.collect::<Vec<_>>();
+		if transfers.is_empty() {
+			return Ok(());
+		}
+
+		ensure!(<Balance<T>>::exists(asset_id, from), Error::<T>::AccountNotExist);
+		Self::ensure_not_frozen(asset_id, from)?;
+
+		for (to, _) in transfers.iter() {
+			Self::ensure_not_frozen(asset_id, to)?;
+		}
+		let (_, total) = Self::ensure_can_create_all(asset_id, &transfers)?;
+
+		let free_balance = Self::free_balance_of(asset_id, from);
+		ensure!(free_balance >= total, Error::<T>::AmountTooLow);
+		Self::ensure_can_withdraw(asset_id, from, WithdrawReason::Transfer.into(), free_balance - total)?;
+
+		// nothing can fail from here on: the sender covers the total and no balance can overflow the supply
+		Self::snapshot_account(asset_id, from);
+		for (to, amount) in transfers {
+			Self::snapshot_account(asset_id, &to);
+			<Balance<T>>::mutate(asset_id, &to, |v| *v = v.saturating_add(amount));
+			<Balance<T>>::mutate(asset_id, from, |v| *v = v.saturating_sub(amount));
+			Self::deposit_event(RawEvent::TokenTransferred(*asset_id, from.clone(), to, amount));
+		}
+
+		Self::reap_if_dust(asset_id, from);
 		Ok(())
 	}
 
-	fn free_balance(account: T::AccountId) -> T::Balance {
-		Self::free_balance_of(&account)
+	/// Make sure crediting `amount` to `who` doesn't open an account below the minimum balance.
+	pub fn ensure_can_create(asset_id: &T::AssetId, who: &T::AccountId, amount: T::Balance) -> DispatchResult {
+		let is_new = Self::free_balance_of(asset_id, who).is_zero() && Self::reserved_balance_of(asset_id, who).is_zero();
+		ensure!(!is_new || amount >= Self::minimum_balance(asset_id), Error::<T>::BelowMinimumBalance);
+		Ok(())
+	}
+
+	/// Make sure crediting every `(recipient, amount)` of `credits` opens no account below the minimum balance,
+	/// adding up the amounts of a recipient listed more than once. Returns the amount credited to each recipient
+	/// and the total.
+	fn ensure_can_create_all(
+		asset_id: &T::AssetId,
+		credits: &[(T::AccountId, T::Balance)],
+	) -> Result<(BTreeMap<T::AccountId, T::Balance>, T::Balance), DispatchError> {
+		let mut per_recipient = BTreeMap::<T::AccountId, T::Balance>::new();
+		let mut total = T::Balance::zero();
+		for (to, amount) in credits.iter() {
+			let credited = per_recipient.entry(to.clone()).or_insert_with(Zero::zero);
+			*credited = credited.checked_add(amount).ok_or(Error::<T>::OverFlowHappens)?;
+			total = total.checked_add(amount).ok_or(Error::<T>::OverFlowHappens)?;
+		}
+		for (to, amount) in per_recipient.iter() {
+			Self::ensure_can_create(asset_id, to, *amount)?;
+		}
+		Ok((per_recipient, total))
 	}
 
-	fn reserved_balance(account: T::AccountId) -> T::Balance {
-		Self::reserved_balance_of(&account)
+	/// Whether the account of `who` would be reaped if left with `free`: both its free balance and its reserve
+	/// are below the minimum balance, nothing of it is held in a tagged reserve, and nothing of it is locked by
+	/// vesting or an active lock.
+	fn is_dust(asset_id: &T::AssetId, who: &T::AccountId, free: T::Balance) -> bool {
+		let minimum_balance = Self::minimum_balance(asset_id);
+		let reserved = Self::reserved_balance_of(asset_id, who);
+		let untagged = Self::named_reserve_of(asset_id, &(who.clone(), T::ReserveIdentifier::default()));
+		let below_minimum = |amount: T::Balance| amount.is_zero() || amount < minimum_balance;
+		reserved == untagged && below_minimum(free) && below_minimum(reserved) && !Self::is_locked(asset_id, who)
 	}
-} 
+
+	/// Whether some of `who`'s balance is held by its vesting schedule or a lock not yet expired.
+	fn is_locked(asset_id: &T::AssetId, who: &T::AccountId) -> bool {
+		let now = <system::Module<T>>::block_number();
+		!Self::vesting_locked(asset_id, who).is_zero()
+			|| Self::locks(asset_id, who).iter().any(|l| now < l.until && !l.amount.is_zero())
+	}
+
+	/// Remove the account of `who` if both its free balance and its reserve fell below the minimum balance,
+	/// burning what is left and handing it to the dust handler. Accounts with locked funds are never reaped,
+	/// expired locks belong to the pallets that set them, so they are left for those to remove.
+	fn reap_if_dust(asset_id: &T::AssetId, who: &T::AccountId) {
+		let free = Self::free_balance_of(asset_id, who);
+		if !<Balance<T>>::exists(asset_id, who) || !Self::is_dust(asset_id, who, free) {
+			return;
+		}
+
+		let reserved = Self::reserved_balance_of(asset_id, who);
+		let dust = free.saturating_add(reserved);
+		Self::snapshot_account(asset_id, who);
+		Self::snapshot_supply(asset_id);
+		<Balance<T>>::remove(asset_id, who);
+		<Reserved<T>>::remove(asset_id, who);
+		if !reserved.is_zero() {
+			let default_id = T::ReserveIdentifier::default();
+			<NamedReserves<T>>::remove(asset_id, &(who.clone(), default_id));
+			Self::deposit_event(RawEvent::ReserveSlashed(*asset_id, who.clone(), default_id, reserved));
+		}
+		<Vesting<T>>::remove(asset_id, who);
+		<TotalSupply<T>>::mutate(asset_id, |v| *v = v.saturating_sub(dust));
+
+		T::DustHandler::on_dust_removal(asset_id, who, dust);
+		Self::deposit_event(RawEvent::AccountReaped(*asset_id, who.clone(), dust));
+	}
+
+	/// Amount of `who`'s free balance still locked by its vesting schedule at the current block.
+	fn vesting_locked(asset_id: &T::AssetId, who: &T::AccountId) -> T::Balance {
+		Self::vesting(asset_id, who).map_or(Zero::zero(), |schedule| {
+			schedule.locked_at::<T::BlockNumberToBalance>(<system::Module<T>>::block_number())
+		})
+	}
+
+	/// Make sure `who`'s free balance may drop to `new_free` for `reasons` without touching locked funds.
+	fn ensure_can_withdraw(
+		asset_id: &T::AssetId,
+		who: &T::AccountId,
+		reasons: WithdrawReasons,
+		new_free: T::Balance,
+	) -> DispatchResult {
+		ensure!(new_free >= Self::vesting_locked(asset_id, who), Error::<T>::LiquidityRestrictions);
+
+		let now = <system::Module<T>>::block_number();
+		let unlocked = Self::locks(asset_id, who)
+			.into_iter()
+			.all(|l| now >= l.until || new_free >= l.amount || !l.reasons.intersects(reasons));
+		ensure!(unlocked, Error::<T>::LiquidityRestrictions);
+		Ok(())
+	}
+
+	fn ensure_owner(asset_id: &T::AssetId, who: &T::AccountId) -> DispatchResult {
+		let owner = Self::token_owner(asset_id).ok_or(Error::<T>::AssetNotExist)?;
+		ensure!(*who == owner, Error::<T>::OnlyOwnerCanOperate);
+		Ok(())
+	}
+
+	/// Make sure the asset isn't paused and `who` isn't frozen on it.
+	pub fn ensure_not_frozen(asset_id: &T::AssetId, who: &T::AccountId) -> DispatchResult {
+		ensure!(!Self::is_paused(asset_id), Error::<T>::AssetPaused);
+		ensure!(!Self::is_frozen(asset_id, who), Error::<T>::AccountFrozen);
+		Ok(())
+	}
+
+	/// Make sure `who` is the owner of the asset or was granted `role` on it.
+	fn ensure_role(asset_id: &T::AssetId, role: Role, who: &T::AccountId) -> DispatchResult {
+		let owner = Self::token_owner(asset_id).ok_or(Error::<T>::AssetNotExist)?;
+		ensure!(*who == owner || Self::has_role(asset_id, &(role, who.clone())), Error::<T>::MissingRole);
+		Ok(())
+	}
+
+	/// Create `amount` new tokens in the free balance of `to`, growing the total supply alike.
+	fn do_mint(asset_id: &T::AssetId, to: &T::AccountId, amount: T::Balance) -> DispatchResult {
+		Self::ensure_can_create(asset_id, to, amount)?;
+		let total_supply_new = Self::total_supply(asset_id).checked_add(&amount).ok_or(Error::<T>::OverFlowHappens)?;
+		let free_new = Self::free_balance_of(asset_id, to).checked_add(&amount).ok_or(Error::<T>::OverFlowHappens)?;
+
+		Self::snapshot_account(asset_id, to);
+		Self::snapshot_supply(asset_id);
+		<Balance<T>>::insert(asset_id, to, free_new);
+		<TotalSupply<T>>::insert(asset_id, total_supply_new);
+
+		Self::deposit_event(RawEvent::Minted(*asset_id, to.clone(), amount));
+		Ok(())
+	}
+
+	/// Destroy `amount` tokens from the free balance of `from`, shrinking the total supply alike.
+	/// Tokens locked by vesting or a lock can't be burned.
+	fn do_burn(asset_id: &T::AssetId, from: &T::AccountId, amount: T::Balance) -> DispatchResult {
+		let free_old = Self::free_balance_of(asset_id, from);
+		ensure!(free_old >= amount, Error::<T>::AmountTooLow);
+		let free_new = free_old.checked_sub(&amount).ok_or(Error::<T>::UnderFlowHappens)?;
+		Self::ensure_can_withdraw(asset_id, from, WithdrawReason::Transfer.into(), free_new)?;
+		let total_supply_new = Self::total_supply(asset_id).checked_sub(&amount).ok_or(Error::<T>::UnderFlowHappens)?;
+
+		Self::snapshot_account(asset_id, from);
+		Self::snapshot_supply(asset_id);
+		<Balance<T>>::insert(asset_id, from, free_new);
+		<TotalSupply<T>>::insert(asset_id, total_supply_new);
+
+		Self::deposit_event(RawEvent::Burned(*asset_id, from.clone(), amount));
+		Self::reap_if_dust(asset_id, from);
+		Ok(())
+	}
+
+	fn set_named_reserve(asset_id: &T::AssetId, who: &T::AccountId, id: &T::ReserveIdentifier, amount: T::Balance) {
+		let key = (who.clone(), *id);
+		if amount.is_zero() {
+			<NamedReserves<T>>::remove(asset_id, &key);
+		} else {
+			<NamedReserves<T>>::insert(asset_id, &key, amount);
+		}
+	}
+
+	/// Make sure `amount` of `who`'s free balance can be moved into a reserve.
+	pub fn ensure_can_reserve(asset_id: &T::AssetId, who: &T::AccountId, amount: T::Balance) -> DispatchResult {
+		Self::ensure_not_frozen(asset_id, who)?;
+
+		let free_old = Self::free_balance_of(asset_id, who);
+		ensure!(free_old >= amount, Error::<T>::AmountTooLow);
+		Self::ensure_can_withdraw(asset_id, who, WithdrawReason::Reserve.into(), free_old - amount)?;
+		Self::reserved_balance_of(asset_id, who).checked_add(&amount).ok_or(Error::<T>::OverFlowHappens)?;
+		Ok(())
+	}
+
+	/// Make sure `amount` of `who`'s `id` reserve can be moved back to its free balance.
+	pub fn ensure_can_unreserve(asset_id: &T::AssetId, who: &T::AccountId, id: &T::ReserveIdentifier, amount: T::Balance) -> DispatchResult {
+		Self::ensure_not_frozen(asset_id, who)?;
+
+		ensure!(Self::named_reserve_of(asset_id, &(who.clone(), *id)) >= amount, Error::<T>::AmountTooLow);
+		Self::reserved_balance_of(asset_id, who).checked_sub(&amount).ok_or(Error::<T>::UnderFlowHappens)?;
+		Self::free_balance_of(asset_id, who).checked_add(&amount).ok_or(Error::<T>::OverFlowHappens)?;
+		Ok(())
+	}
+
+	/// Move `amount` of `who`'s free balance into its `id` reserve.
+	pub fn do_reserve(asset_id: &T::AssetId, who: &T::AccountId, id: &T::ReserveIdentifier, amount: T::Balance) -> DispatchResult {
+		Self::ensure_can_reserve(asset_id, who, amount)?;
+
+		// a tagged reserve is part of the whole reserve, which was checked not to overflow
+		let free_new = Self::free_balance_of(asset_id, who) - amount;
+		let reserved_new = Self::reserved_balance_of(asset_id, who) + amount;
+		let named_new = Self::named_reserve_of(asset_id, &(who.clone(), *id)) + amount;
+
+		<Balance<T>>::insert(asset_id, who, free_new);
+		<Reserved<T>>::insert(asset_id, who, reserved_new);
+		Self::set_named_reserve(asset_id, who, id, named_new);
+
+		Self::deposit_event(RawEvent::TokenReserved(*asset_id, who.clone(), *id, amount));
+		Ok(())
+	}
+
+	/// Move `amount` out of `who`'s `id` reserve back into its free balance.
+	pub fn do_unreserve(asset_id: &T::AssetId, who: &T::AccountId, id: &T::ReserveIdentifier, amount: T::Balance) -> DispatchResult {
+		Self::ensure_can_unreserve(asset_id, who, id, amount)?;
+
+		// checked above
+		let named_new = Self::named_reserve_of(asset_id, &(who.clone(), *id)) - amount;
+		let reserved_new = Self::reserved_balance_of(asset_id, who) - amount;
+		let free_new = Self::free_balance_of(asset_id, who) + amount;
+
+		<Balance<T>>::insert(asset_id, who, free_new);
+		<Reserved<T>>::insert(asset_id, who, reserved_new);
+		Self::set_named_reserve(asset_id, who, id, named_new);
+
+		Self::deposit_event(RawEvent::TokenUnreserved(*asset_id, who.clone(), *id, amount));
+		Ok(())
+	}
+
+	/// Destroy `amount` out of `who`'s `id` reserve, shrinking the total supply alike.
+	fn do_slash_reserved(asset_id: &T::AssetId, who: &T::AccountId, id: &T::ReserveIdentifier, amount: T::Balance) -> DispatchResult {
+		let named_old = Self::named_reserve_of(asset_id, &(who.clone(), *id));
+		ensure!(named_old >= amount, Error::<T>::AmountTooLow);
+		let named_new = named_old.checked_sub(&amount).ok_or(Error::<T>::UnderFlowHappens)?;
+		let reserved_new = Self::reserved_balance_of(asset_id, who).checked_sub(&amount).ok_or(Error::<T>::UnderFlowHappens)?;
+		let total_supply_new = Self::total_supply(asset_id).checked_sub(&amount).ok_or(Error::<T>::UnderFlowHappens)?;
+
+		Self::snapshot_account(asset_id, who);
+		Self::snapshot_supply(asset_id);
+		<Reserved<T>>::insert(asset_id, who, reserved_new);
+		Self::set_named_reserve(asset_id, who, id, named_new);
+		<TotalSupply<T>>::insert(asset_id, total_supply_new);
+
+		Self::deposit_event(RawEvent::ReserveSlashed(*asset_id, who.clone(), *id, amount));
+		Self::reap_if_dust(asset_id, who);
+		Ok(())
+	}
+
+	/// Move `amount` out of `from`'s `id` reserve into the free balance of `to`.
+	pub fn do_repatriate_reserved(
+		asset_id: &T::AssetId,
+		from: &T::AccountId,
+		id: &T::ReserveIdentifier,
+		to: &T::AccountId,
+		amount: T::Balance,
+	) -> DispatchResult {
+		if from == to {
+			return Self::do_unreserve(asset_id, from, id, amount);
+		}
+		Self::ensure_not_frozen(asset_id, from)?;
+		Self::ensure_not_frozen(asset_id, to)?;
+
+		let named_old = Self::named_reserve_of(asset_id, &(from.clone(), *id));
+		ensure!(named_old >= amount, Error::<T>::AmountTooLow);
+		let named_new = named_old.checked_sub(&amount).ok_or(Error::<T>::UnderFlowHappens)?;
+		let reserved_new = Self::reserved_balance_of(asset_id, from).checked_sub(&amount).ok_or(Error::<T>::UnderFlowHappens)?;
+		let free_new_to = Self::free_balance_of(asset_id, to).checked_add(&amount).ok_or(Error::<T>::OverFlowHappens)?;
+		Self::ensure_can_create(asset_id, to, amount)?;
+
+		Self::snapshot_account(asset_id, from);
+		Self::snapshot_account(asset_id, to);
+		<Reserved<T>>::insert(asset_id, from, reserved_new);
+		Self::set_named_reserve(asset_id, from, id, named_new);
+		<Balance<T>>::insert(asset_id, to, free_new_to);
+
+		Self::deposit_event(RawEvent::ReserveRepatriated(*asset_id, from.clone(), to.clone(), *id, amount));
+		Self::reap_if_dust(asset_id, from);
+		Ok(())
+	}
+
+	/// Settle `payer`'s `id` reserve: pay `to_payee` of it out to `payee` and move `to_payer` back into `payer`'s
+	/// free balance. Everything is checked first, so that either both happen or neither does.
+	pub fn do_settle_reserved(
+		asset_id: &T::AssetId,
+		payer: &T::AccountId,
+		id: &T::ReserveIdentifier,
+		payee: &T::AccountId,
+		to_payee: T::Balance,
+		to_payer: T::Balance,
+	) -> DispatchResult {
+		Self::ensure_not_frozen(asset_id, payer)?;
+		let total = to_payee.checked_add(&to_payer).ok_or(Error::<T>::OverFlowHappens)?;
+		ensure!(Self::named_reserve_of(asset_id, &(payer.clone(), *id)) >= total, Error::<T>::AmountTooLow);
+		Self::free_balance_of(asset_id, payer).checked_add(&to_payer).ok_or(Error::<T>::OverFlowHappens)?;
+		if !to_payee.is_zero() {
+			Self::ensure_not_frozen(asset_id, payee)?;
+			Self::ensure_can_create(asset_id, payee, to_payee)?;
+			Self::free_balance_of(asset_id, payee).checked_add(&to_payee).ok_or(Error::<T>::OverFlowHappens)?;
+		}
+
+		// nothing can fail from here on, both moves were checked above
+		if !to_payee.is_zero() {
+			Self::do_repatriate_reserved(asset_id, payer, id, payee, to_payee)?;
+		}
+		if !to_payer.is_zero() {
+			Self::do_unreserve(asset_id, payer, id, to_payer)?;
+		}
+		Ok(())
+	}
+
+	/// Take a new snapshot of the asset, returning its id.
+	pub fn do_snapshot(asset_id: &T::AssetId) -> Result<SnapshotId, DispatchError> {
+		let snapshot_id = Self::current_snapshot(asset_id).checked_add(1).ok_or(Error::<T>::OverFlowHappens)?;
+		<CurrentSnapshot<T>>::insert(asset_id, snapshot_id);
+
+		Self::deposit_event(RawEvent::SnapshotTaken(*asset_id, snapshot_id));
+		Ok(snapshot_id)
+	}
+
+	/// Record the total balance of `who` if it is about to change for the first time since the latest snapshot.
+	/// Must be called before any change to the free or reserved balance of `who`.
+	fn snapshot_account(asset_id: &T::AssetId, who: &T::AccountId) {
+		let current = Self::current_snapshot(asset_id);
+		let count = Self::account_snapshot_count(asset_id, who);
+		if current == 0 || (count > 0 && Self::account_snapshot(asset_id, &(who.clone(), count - 1)).0 >= current) {
+			return;
+		}
+
+		let balance = Self::total_balance(*asset_id, who.clone());
+		<AccountSnapshots<T>>::insert(asset_id, &(who.clone(), count), (current, balance));
+		<AccountSnapshotCount<T>>::insert(asset_id, who, count + 1);
+	}
+
+	/// Record the total supply if it is about to change for the first time since the latest snapshot.
+	/// Must be called before any change to the total supply.
+	fn snapshot_supply(asset_id: &T::AssetId) {
+		let current = Self::current_snapshot(asset_id);
+		let count = Self::supply_snapshot_count(asset_id);
+		if current == 0 || (count > 0 && Self::supply_snapshot(asset_id, count - 1).0 >= current) {
+			return;
+		}
+
+		<SupplySnapshots<T>>::insert(asset_id, count, (current, Self::total_supply(asset_id)));
+		<SupplySnapshotCount<T>>::insert(asset_id, count + 1);
+	}
+
+	/// Value at snapshot `snapshot_id` out of the `count` records `record` reads by index: the first one recorded
+	/// at or after it, or `current` if it hasn't changed since.
+	fn value_at(
+		asset_id: &T::AssetId,
+		snapshot_id: SnapshotId,
+		count: u32,
+		record: impl Fn(u32) -> (SnapshotId, T::Balance),
+		current: T::Balance,
+	) -> Option<T::Balance> {
+		if snapshot_id == 0 || snapshot_id > Self::current_snapshot(asset_id) {
+			return None;
+		}
+
+		// first record with a snapshot id not below `snapshot_id`, found in as many reads as there are bits in `count`
+		let (mut low, mut high) = (0, count);
+		while low < high {
+			let middle = low + (high - low) / 2;
+			if record(middle).0 < snapshot_id {
+				low = middle + 1;
+			} else {
+				high = middle;
+			}
+		}
+		Some(if low < count { record(low).1 } else { current })
+	}
+
+	/// Total balance of `who` when snapshot `snapshot_id` of the asset was taken, `None` if there is no such snapshot.
+	pub fn balance_at(asset_id: T::AssetId, who: T::AccountId, snapshot_id: SnapshotId) -> Option<T::Balance> {
+		let count = Self::account_snapshot_count(&asset_id, &who);
+		let record = |index: u32| Self::account_snapshot(&asset_id, &(who.clone(), index));
+		Self::value_at(&asset_id, snapshot_id, count, record, Self::total_balance(asset_id, who.clone()))
+	}
+
+	/// Total supply of the asset when snapshot `snapshot_id` was taken, `None` if there is no such snapshot.
+	pub fn total_supply_at(asset_id: T::AssetId, snapshot_id: SnapshotId) -> Option<T::Balance> {
+		let count = Self::supply_snapshot_count(&asset_id);
+		let record = |index: u32| Self::supply_snapshot(&asset_id, index);
+		Self::value_at(&asset_id, snapshot_id, count, record, Self::total_supply(&asset_id))
+	}
+
+	/// What an owner signs to permit `spender` to spend `amount` of the asset, with the owner's current
+	/// permit nonce.
+	pub fn permit_payload(
+		asset_id: &T::AssetId,
+		owner: &T::AccountId,
+		spender: &T::AccountId,
+		amount: T::Balance,
+		nonce: u64,
+		deadline: T::BlockNumber,
+	) -> Vec<u8> {
+		(PERMIT_CONTEXT, asset_id, owner, spender, amount, nonce, deadline).encode()
+	}
+
+	fn set_allowance(asset_id: &T::AssetId, owner: &T::AccountId, spender: &T::AccountId, amount: T::Balance) {
+		let key = (owner.clone(), spender.clone());
+		if amount.is_zero() {
+			<Allowances<T>>::remove(asset_id, &key);
+		} else {
+			<Allowances<T>>::insert(asset_id, &key, amount);
+		}
+
+		Self::deposit_event(RawEvent::Approval(*asset_id, owner.clone(), spender.clone(), amount));
+	}
+
+	pub fn free_balance(asset_id: T::AssetId, account: T::AccountId) -> T::Balance {
+		Self::free_balance_of(&asset_id, &account)
+	}
+
+	pub fn reserved_balance(asset_id: T::AssetId, account: T::AccountId) -> T::Balance {
+		Self::reserved_balance_of(&asset_id, &account)
+	}
+
+	/// Free plus reserved balance of `account`.
+	pub fn total_balance(asset_id: T::AssetId, account: T::AccountId) -> T::Balance {
+		Self::free_balance_of(&asset_id, &account).saturating_add(Self::reserved_balance_of(&asset_id, &account))
+	}
+}
 
 decl_event!(
 	pub enum Event<T>
 	where
 		AccountId = <T as system::Trait>::AccountId,
 		Balance = <T as Trait>::Balance,
+		AssetId = <T as Trait>::AssetId,
+		ReserveIdentifier = <T as Trait>::ReserveIdentifier,
 	{
-		TokenIssued(AccountId, Balance),
-		BalanceSet(AccountId, Balance, Balance),
-		TokenTransferred(AccountId, AccountId, Balance),
+		/// A new asset was created (asset, owner, total supply).
+		AssetCreated(AssetId, AccountId, Balance),
+		/// Ownership of an asset moved (asset, old owner, new owner).
+		OwnershipTransferred(AssetId, AccountId, AccountId),
+		RoleGranted(AssetId, Role, AccountId),
+		RoleRevoked(AssetId, Role, AccountId),
+		Paused(AssetId),
+		Unpaused(AssetId),
+		Frozen(AssetId, AccountId),
+		Thawed(AssetId, AccountId),
+		BalanceSet(AssetId, AccountId, Balance, Balance),
+		TokenTransferred(AssetId, AccountId, AccountId, Balance),
+		/// A transfer with a payment reference was made (asset, from, to, amount, memo).
+		TokenTransferredWithMemo(AssetId, AccountId, AccountId, Balance, Vec<u8>),
+		/// Allowance of a spender over an owner's balance was set (asset, owner, spender, new allowance).
+		Approval(AssetId, AccountId, AccountId, Balance),
+		Minted(AssetId, AccountId, Balance),
+		Burned(AssetId, AccountId, Balance),
+		/// The amount still locked by a vesting schedule was updated (asset, account, locked).
+		VestingUpdated(AssetId, AccountId, Balance),
+		/// A vesting schedule was fully unlocked and removed.
+		VestingCompleted(AssetId, AccountId),
+		/// Funds were moved from free balance into a reserve (asset, account, reserve id, amount).
+		TokenReserved(AssetId, AccountId, ReserveIdentifier, Balance),
+		/// Funds were moved from a reserve back into free balance (asset, account, reserve id, amount).
+		TokenUnreserved(AssetId, AccountId, ReserveIdentifier, Balance),
+		/// Reserved funds were destroyed (asset, account, reserve id, amount).
+		ReserveSlashed(AssetId, AccountId, ReserveIdentifier, Balance),
+		/// Reserved funds were moved to another account's free balance (asset, from, to, reserve id, amount).
+		ReserveRepatriated(AssetId, AccountId, AccountId, ReserveIdentifier, Balance),
+		/// An airdrop was minted (asset, number of distinct accounts paid, total minted).
+		Airdropped(AssetId, u32, Balance),
+		/// Metadata of an asset was set (asset, name, symbol, decimals).
+		MetadataSet(AssetId, Vec<u8>, Vec<u8>, u8),
+		/// A snapshot of an asset was taken (asset, snapshot id).
+		SnapshotTaken(AssetId, SnapshotId),
+		/// A payment stream was created (stream id, asset, payer, payee, total amount over its period).
+		StreamCreated(StreamId, AssetId, AccountId, AccountId, Balance),
+		/// The payee withdrew from a stream (stream id, amount).
+		StreamWithdrawn(StreamId, Balance),
+		/// A stream was cancelled (stream id, paid to payee, refunded to payer).
+		StreamCancelled(StreamId, Balance, Balance),
+		/// The minimum balance of an asset was changed.
+		MinimumBalanceSet(AssetId, Balance),
+		/// An account fell below the minimum balance and was removed (asset, account, dust burned, free and reserved).
+		AccountReaped(AssetId, AccountId, Balance),
 	}
 );
 
@@ -175,5 +1271,30 @@ decl_error! {
 		AccountNotExist,
 		OverFlowHappens,
 		UnderFlowHappens,
+		AllowanceTooLow,
+		AssetNotExist,
+		NotVesting,
+		LiquidityRestrictions,
+		MissingRole,
+		AssetPaused,
+		AccountFrozen,
+		/// The account would be left, or opened, with less than the minimum balance
+		BelowMinimumBalance,
+		/// Name or symbol longer than `MAX_METADATA_LENGTH`
+		MetadataTooLong,
+		/// Memo longer than `MAX_MEMO_LENGTH`
+		MemoTooLong,
+		/// A transfer with a memo must go to another account
+		TransferToSelf,
+		/// A stream has to start now or later and end after it starts
+		InvalidStreamPeriod,
+		StreamNotExist,
+		NotStreamPayee,
+		/// Only the payer or the payee may cancel a stream
+		NotStreamParty,
+		/// The deadline of the permit has passed
+		PermitExpired,
+		/// The permit isn't signed by the owner, or not with their current nonce
+		InvalidSignature,
 	}
 }
@@ -4,9 +4,10 @@ use super::*;
 
 use frame_support::{impl_outer_origin, parameter_types, weights::Weight};
 use sp_core::H256;
+use std::cell::RefCell;
 use sp_runtime::{
-	testing::Header,
-	traits::{BlakeTwo256, IdentityLookup},
+	testing::{Header, TestSignature, UintAuthorityId},
+	traits::{BlakeTwo256, ConvertInto, IdentityLookup},
 	Perbill,
 };
 //use randomness_collective_flip;
@@ -25,9 +26,13 @@ parameter_types! {
 	pub const MaximumBlockWeight: Weight = 1024;
 	pub const MaximumBlockLength: u32 = 2 * 1024;
 	pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+	pub const RkxAssetId: AssetId = 0;
+	pub const StreamReserveId: ReserveIdentifier = *b"stream  ";
 }
 
 pub type Balance = u64;
+pub type AssetId = u32;
+pub type ReserveIdentifier = [u8; 8];
 
 impl system::Trait for TestRuntime {
 	type Origin = Origin;
@@ -48,21 +53,65 @@ impl system::Trait for TestRuntime {
 	type ModuleToIndex = ();
 }
 
+thread_local! {
+	static DUST: RefCell<Vec<(AssetId, u64, Balance)>> = RefCell::new(vec![]);
+}
+
+/// Records the dust of every reaped account.
+pub struct RecordDust;
+impl OnDustRemoval<AssetId, u64, Balance> for RecordDust {
+	fn on_dust_removal(asset_id: &AssetId, who: &u64, amount: Balance) {
+		DUST.with(|d| d.borrow_mut().push((*asset_id, *who, amount)));
+	}
+}
+
+pub fn dust_removed() -> Vec<(AssetId, u64, Balance)> {
+	DUST.with(|d| d.borrow().clone())
+}
+
 impl Trait for TestRuntime {
 	type Event = ();
 	type Balance = Balance;
+	type AssetId = AssetId;
+	type BlockNumberToBalance = ConvertInto;
+	type ReserveIdentifier = ReserveIdentifier;
+	type DustHandler = RecordDust;
+	type StreamReserveId = StreamReserveId;
+	type Signature = TestSignature;
+	type Signer = UintAuthorityId;
 }
 
 pub type PaymentToken = Module<TestRuntime>;
+pub type System = system::Module<TestRuntime>;
+pub type Rkx = AssetCurrency<TestRuntime, RkxAssetId>;
 
 // This function basically just builds a genesis storage key/value store according to
 // our desired mockup.
 
 pub fn build_ext() -> sp_io::TestExternalities {
+	build_ext_with_vesting(vec![])
+}
+
+pub fn build_ext_with_vesting(
+	vesting: Vec<(AssetId, u64, Balance, Balance, u64, u64)>,
+) -> sp_io::TestExternalities {
+	build_ext_with(vesting, vec![])
+}
+
+pub fn build_ext_with_minimum_balance(minimum_balance: Balance) -> sp_io::TestExternalities {
+	build_ext_with(vec![], vec![(0, minimum_balance)])
+}
+
+fn build_ext_with(
+	vesting: Vec<(AssetId, u64, Balance, Balance, u64, u64)>,
+	minimum_balances: Vec<(AssetId, Balance)>,
+) -> sp_io::TestExternalities {
 	let mut t = system::GenesisConfig::default().build_storage::<TestRuntime>().unwrap();
 	GenesisConfig::<TestRuntime> {
-		token_owner: 1,
-		total_supply: 21000000,
+		assets: vec![(0, 1, 21000000)],
+		vesting,
+		minimum_balances,
+		metadata: vec![(0, b"RKX Token".to_vec(), b"RKX".to_vec(), 12)],
 	}.assimilate_storage(&mut t).unwrap();
 	t.into()
 }
@@ -1,124 +1,1003 @@
 use super::*;
-use frame_support::{assert_noop, assert_ok};
-use mock::{build_ext, PaymentToken, Origin, TestRuntime};
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{Currency, ExistenceRequirement, LockableCurrency, ReservableCurrency, WithdrawReasons},
+	weights::GetDispatchInfo,
+	StorageDoubleMap,
+};
+use mock::{
+	build_ext, build_ext_with_minimum_balance, build_ext_with_vesting, dust_removed, PaymentToken, Origin, Rkx, System,
+	TestRuntime,
+};
 use sp_core::H256;
+use sp_runtime::testing::TestSignature;
 
-#[test]
-fn issue_token_owner_check_ok() {
-	build_ext().execute_with(|| {
-		//wrong owner of token system
-		assert_noop!(PaymentToken::issue_token(Origin::signed(5)), Error::<TestRuntime>::OnlyOwnerCanOperate);
-		assert_ok!(PaymentToken::issue_token(Origin::signed(1)));
-	})
-}
+const DEFAULT_RESERVE: [u8; 8] = [0; 8];
+const DEPOSIT: [u8; 8] = *b"deposit ";
+const BOND: [u8; 8] = *b"bond    ";
+const STAKING: [u8; 8] = *b"staking ";
+const STREAM: [u8; 8] = *b"stream  ";
 
 #[test]
-fn issue_token_amount_ok() {
+fn genesis_issue_ok() {
 	build_ext().execute_with(|| {
-		assert_ok!(PaymentToken::issue_token(Origin::signed(1)));
-		assert_eq!(PaymentToken::free_balance(1), 21000000);
+		//whole supply is issued to the owner at genesis
+		assert_eq!(PaymentToken::free_balance(0, 1), 21000000);
+		assert_eq!(PaymentToken::total_supply(0), 21000000);
 	})
 }
 
 #[test]
 fn set_balance_owner_ok() {
 	build_ext().execute_with(|| {
-		assert_noop!(PaymentToken::set_balance(Origin::signed(5), 5, 1000, 2000), Error::<TestRuntime>::OnlyOwnerCanOperate);
-		assert_ok!(PaymentToken::set_balance(Origin::signed(1), 5, 1000, 2000));
+		assert_noop!(PaymentToken::set_balance(Origin::signed(5), 0, 5, 1000, 2000), Error::<TestRuntime>::OnlyOwnerCanOperate);
+		assert_ok!(PaymentToken::set_balance(Origin::signed(1), 0, 5, 1000, 2000));
 	})
 }
 
 #[test]
 fn set_balance_amount_ok() {
 	build_ext().execute_with(|| {
-		assert_ok!(PaymentToken::set_balance(Origin::signed(1), 5, 1000, 2000));
-		assert_eq!(PaymentToken::free_balance(5), 1000);
-		assert_eq!(PaymentToken::reserved_balance(5), 2000);
+		assert_ok!(PaymentToken::set_balance(Origin::signed(1), 0, 5, 1000, 2000));
+		assert_eq!(PaymentToken::free_balance(0, 5), 1000);
+		assert_eq!(PaymentToken::reserved_balance(0, 5), 2000);
 	})
 }
 
 #[test]
 fn transfer_from_amount_not_enough() {
 	build_ext().execute_with(|| {
-		assert_ok!(PaymentToken::set_balance(Origin::signed(1), 5, 1000, 2000));
+		assert_ok!(PaymentToken::set_balance(Origin::signed(1), 0, 5, 1000, 2000));
 		//not enough balance
-		assert_noop!(PaymentToken::transfer_from(Origin::signed(5), 2, 1001), Error::<TestRuntime>::AmountTooLow);
-		assert_ok!(PaymentToken::transfer_from(Origin::signed(5), 2, 1000));
+		assert_noop!(PaymentToken::transfer_from(Origin::signed(5), 0, 2, 1001), Error::<TestRuntime>::AmountTooLow);
+		assert_ok!(PaymentToken::transfer_from(Origin::signed(5), 0, 2, 1000));
 	})
 }
 
 #[test]
 fn transfer_from_ok() {
 	build_ext().execute_with(|| {
-		assert_ok!(PaymentToken::set_balance(Origin::signed(1), 5, 1000, 2000));
-		assert_ok!(PaymentToken::transfer_from(Origin::signed(5), 2, 600));
-		assert_eq!(PaymentToken::free_balance(5), 400);
-		assert_eq!(PaymentToken::free_balance(2), 600);
+		assert_ok!(PaymentToken::set_balance(Origin::signed(1), 0, 5, 1000, 2000));
+		assert_ok!(PaymentToken::transfer_from(Origin::signed(5), 0, 2, 600));
+		assert_eq!(PaymentToken::free_balance(0, 5), 400);
+		assert_eq!(PaymentToken::free_balance(0, 2), 600);
 	})
 }
 
 #[test]
 fn reserve_owner() {
 	build_ext().execute_with(|| {
-		assert_ok!(PaymentToken::set_balance(Origin::signed(1), 5, 1000, 2000));
+		assert_ok!(PaymentToken::set_balance(Origin::signed(1), 0, 5, 1000, 2000));
 		//wrong owner
-		assert_noop!(PaymentToken::reserve_token(Origin::signed(2), 5, 600), Error::<TestRuntime>::OnlyOwnerCanOperate);
-		assert_noop!(PaymentToken::unreserve_token(Origin::signed(3), 5, 600), Error::<TestRuntime>::OnlyOwnerCanOperate);
-		assert_ok!(PaymentToken::reserve_token(Origin::signed(1), 5, 600));
-		assert_ok!(PaymentToken::unreserve_token(Origin::signed(1), 5, 600));
+		assert_noop!(PaymentToken::reserve_token(Origin::signed(2), 0, 5, DEFAULT_RESERVE, 600), Error::<TestRuntime>::OnlyOwnerCanOperate);
+		assert_noop!(PaymentToken::unreserve_token(Origin::signed(3), 0, 5, DEFAULT_RESERVE, 600), Error::<TestRuntime>::OnlyOwnerCanOperate);
+		assert_ok!(PaymentToken::reserve_token(Origin::signed(1), 0, 5, DEFAULT_RESERVE, 600));
+		assert_ok!(PaymentToken::unreserve_token(Origin::signed(1), 0, 5, DEFAULT_RESERVE, 600));
 	})
 }
 
 #[test]
 fn reserve_amount() {
 	build_ext().execute_with(|| {
-		assert_ok!(PaymentToken::set_balance(Origin::signed(1), 5, 1000, 2000));
+		assert_ok!(PaymentToken::set_balance(Origin::signed(1), 0, 5, 1000, 2000));
 		//too much
-		assert_noop!(PaymentToken::reserve_token(Origin::signed(1), 5, 1001), Error::<TestRuntime>::AmountTooLow);
+		assert_noop!(PaymentToken::reserve_token(Origin::signed(1), 0, 5, DEFAULT_RESERVE, 1001), Error::<TestRuntime>::AmountTooLow);
 		//check amount correctness of reserve process
-		assert_eq!(PaymentToken::reserved_balance(5), 2000);
+		assert_eq!(PaymentToken::reserved_balance(0, 5), 2000);
 
-		assert_ok!(PaymentToken::reserve_token(Origin::signed(1), 5, 401));
-		assert_eq!(PaymentToken::reserved_balance(5), 2401);
-		assert_eq!(PaymentToken::free_balance(5), 599);
+		assert_ok!(PaymentToken::reserve_token(Origin::signed(1), 0, 5, DEFAULT_RESERVE, 401));
+		assert_eq!(PaymentToken::reserved_balance(0, 5), 2401);
+		assert_eq!(PaymentToken::free_balance(0, 5), 599);
 
-		assert_ok!(PaymentToken::reserve_token(Origin::signed(1), 5, 599));
-		assert_eq!(PaymentToken::reserved_balance(5), 3000);
-		assert_eq!(PaymentToken::free_balance(5), 0);
+		assert_ok!(PaymentToken::reserve_token(Origin::signed(1), 0, 5, DEFAULT_RESERVE, 599));
+		assert_eq!(PaymentToken::reserved_balance(0, 5), 3000);
+		assert_eq!(PaymentToken::free_balance(0, 5), 0);
 
-		assert_noop!(PaymentToken::reserve_token(Origin::signed(1), 5, 1), Error::<TestRuntime>::AmountTooLow);
+		assert_noop!(PaymentToken::reserve_token(Origin::signed(1), 0, 5, DEFAULT_RESERVE, 1), Error::<TestRuntime>::AmountTooLow);
 		//not enough to unreserve
-		assert_noop!(PaymentToken::unreserve_token(Origin::signed(1), 5, 3001), Error::<TestRuntime>::AmountTooLow);
+		assert_noop!(PaymentToken::unreserve_token(Origin::signed(1), 0, 5, DEFAULT_RESERVE, 3001), Error::<TestRuntime>::AmountTooLow);
 		//check amount in unreserve process
-		assert_ok!(PaymentToken::unreserve_token(Origin::signed(1), 5, 2000));
-		assert_eq!(PaymentToken::reserved_balance(5), 1000);
-		assert_eq!(PaymentToken::free_balance(5), 2000);
+		assert_ok!(PaymentToken::unreserve_token(Origin::signed(1), 0, 5, DEFAULT_RESERVE, 2000));
+		assert_eq!(PaymentToken::reserved_balance(0, 5), 1000);
+		assert_eq!(PaymentToken::free_balance(0, 5), 2000);
 
-		assert_ok!(PaymentToken::unreserve_token(Origin::signed(1), 5, 1000));
-		assert_eq!(PaymentToken::reserved_balance(5), 0);
-		assert_eq!(PaymentToken::free_balance(5), 3000);
+		assert_ok!(PaymentToken::unreserve_token(Origin::signed(1), 0, 5, DEFAULT_RESERVE, 1000));
+		assert_eq!(PaymentToken::reserved_balance(0, 5), 0);
+		assert_eq!(PaymentToken::free_balance(0, 5), 3000);
 
-		assert_noop!(PaymentToken::unreserve_token(Origin::signed(1), 5, 1), Error::<TestRuntime>::AmountTooLow);
+		assert_noop!(PaymentToken::unreserve_token(Origin::signed(1), 0, 5, DEFAULT_RESERVE, 1), Error::<TestRuntime>::AmountTooLow);
 	})
 }
 
 #[test]
 fn total_supply_ok() {
 	build_ext().execute_with(|| {
-		assert_eq!(PaymentToken::total_supply(), 21000000);
+		assert_eq!(PaymentToken::total_supply(0), 21000000);
 		//set a balance to increase total supply
-		assert_ok!(PaymentToken::set_balance(Origin::signed(1), 5, 1000000, 2000000));
-		assert_eq!(PaymentToken::total_supply(), 24000000);
+		assert_ok!(PaymentToken::set_balance(Origin::signed(1), 0, 5, 1000000, 2000000));
+		assert_eq!(PaymentToken::total_supply(0), 24000000);
 		//set another account's to increase total supply
-		assert_ok!(PaymentToken::set_balance(Origin::signed(1), 2, 100000, 200000));
-		assert_eq!(PaymentToken::total_supply(), 24300000);
+		assert_ok!(PaymentToken::set_balance(Origin::signed(1), 0, 2, 100000, 200000));
+		assert_eq!(PaymentToken::total_supply(0), 24300000);
 
 		//reduce free and increase reserved to increase total supply
-		assert_ok!(PaymentToken::set_balance(Origin::signed(1), 5, 0, 4000000));
-		assert_eq!(PaymentToken::total_supply(), 25300000);
+		assert_ok!(PaymentToken::set_balance(Origin::signed(1), 0, 5, 0, 4000000));
+		assert_eq!(PaymentToken::total_supply(0), 25300000);
 
 		//increate free and decrease reserved to decrease total supply
-		assert_ok!(PaymentToken::set_balance(Origin::signed(1), 5, 1000000, 2000000));
-		assert_eq!(PaymentToken::total_supply(), 24300000);
+		assert_ok!(PaymentToken::set_balance(Origin::signed(1), 0, 5, 1000000, 2000000));
+		assert_eq!(PaymentToken::total_supply(0), 24300000);
+	})
+}
+
+#[test]
+fn approve_ok() {
+	build_ext().execute_with(|| {
+		assert_ok!(PaymentToken::approve(Origin::signed(5), 0, 2, 700));
+		assert_eq!(PaymentToken::allowance(0, (5, 2)), 700);
+		//approve replaces the old allowance
+		assert_ok!(PaymentToken::approve(Origin::signed(5), 0, 2, 300));
+		assert_eq!(PaymentToken::allowance(0, (5, 2)), 300);
+		assert_eq!(PaymentToken::allowance(0, (2, 5)), 0);
+	})
+}
+
+#[test]
+fn increase_decrease_allowance_ok() {
+	build_ext().execute_with(|| {
+		assert_ok!(PaymentToken::increase_allowance(Origin::signed(5), 0, 2, 500));
+		assert_ok!(PaymentToken::increase_allowance(Origin::signed(5), 0, 2, 200));
+		assert_eq!(PaymentToken::allowance(0, (5, 2)), 700);
+
+		assert_noop!(PaymentToken::decrease_allowance(Origin::signed(5), 0, 2, 701), Error::<TestRuntime>::AllowanceTooLow);
+		assert_ok!(PaymentToken::decrease_allowance(Origin::signed(5), 0, 2, 700));
+		assert_eq!(PaymentToken::allowance(0, (5, 2)), 0);
+	})
+}
+
+#[test]
+fn transfer_approved_ok() {
+	build_ext().execute_with(|| {
+		assert_ok!(PaymentToken::set_balance(Origin::signed(1), 0, 5, 1000, 2000));
+		assert_ok!(PaymentToken::approve(Origin::signed(5), 0, 2, 700));
+		//spender moves funds from owner to a third account
+		assert_ok!(PaymentToken::transfer_approved(Origin::signed(2), 0, 5, 3, 600));
+		assert_eq!(PaymentToken::free_balance(0, 5), 400);
+		assert_eq!(PaymentToken::free_balance(0, 3), 600);
+		assert_eq!(PaymentToken::free_balance(0, 2), 0);
+		assert_eq!(PaymentToken::allowance(0, (5, 2)), 100);
+	})
+}
+
+#[test]
+fn transfer_approved_allowance_too_low() {
+	build_ext().execute_with(|| {
+		assert_ok!(PaymentToken::set_balance(Origin::signed(1), 0, 5, 1000, 2000));
+		//no allowance at all
+		assert_noop!(PaymentToken::transfer_approved(Origin::signed(2), 0, 5, 3, 1), Error::<TestRuntime>::AllowanceTooLow);
+
+		assert_ok!(PaymentToken::approve(Origin::signed(5), 0, 2, 2000));
+		//allowance is enough but balance is not
+		assert_noop!(PaymentToken::transfer_approved(Origin::signed(2), 0, 5, 3, 1001), Error::<TestRuntime>::AmountTooLow);
+
+		assert_ok!(PaymentToken::approve(Origin::signed(5), 0, 2, 100));
+		assert_noop!(PaymentToken::transfer_approved(Origin::signed(2), 0, 5, 3, 101), Error::<TestRuntime>::AllowanceTooLow);
+		assert_eq!(PaymentToken::free_balance(0, 5), 1000);
+	})
+}
+
+#[test]
+fn mint_ok() {
+	build_ext().execute_with(|| {
+		assert_noop!(PaymentToken::mint(Origin::signed(5), 0, 5, 1000), Error::<TestRuntime>::MissingRole);
+		assert_ok!(PaymentToken::mint(Origin::signed(1), 0, 5, 1000));
+		assert_eq!(PaymentToken::free_balance(0, 5), 1000);
+		assert_eq!(PaymentToken::total_supply(0), 21001000);
+	})
+}
+
+#[test]
+fn burn_ok() {
+	build_ext().execute_with(|| {
+		assert_ok!(PaymentToken::set_balance(Origin::signed(1), 0, 5, 1000, 2000));
+		assert_eq!(PaymentToken::total_supply(0), 21003000);
+
+		assert_noop!(PaymentToken::burn(Origin::signed(5), 0, 5, 100), Error::<TestRuntime>::MissingRole);
+		//reserved balance can't be burned
+		assert_noop!(PaymentToken::burn(Origin::signed(1), 0, 5, 1001), Error::<TestRuntime>::AmountTooLow);
+		assert_ok!(PaymentToken::burn(Origin::signed(1), 0, 5, 400));
+		assert_eq!(PaymentToken::free_balance(0, 5), 600);
+		assert_eq!(PaymentToken::reserved_balance(0, 5), 2000);
+		assert_eq!(PaymentToken::total_supply(0), 21002600);
+	})
+}
+
+#[test]
+fn burn_own_ok() {
+	build_ext().execute_with(|| {
+		assert_ok!(PaymentToken::transfer_from(Origin::signed(1), 0, 5, 1000));
+		assert_noop!(PaymentToken::burn_own(Origin::signed(5), 0, 1001), Error::<TestRuntime>::AmountTooLow);
+		assert_ok!(PaymentToken::burn_own(Origin::signed(5), 0, 1000));
+		assert_eq!(PaymentToken::free_balance(0, 5), 0);
+		assert_eq!(PaymentToken::free_balance(0, 1), 20999000);
+		assert_eq!(PaymentToken::total_supply(0), 20999000);
+	})
+}
+
+#[test]
+fn create_asset_ok() {
+	build_ext().execute_with(|| {
+		//genesis asset takes id 0
+		assert_eq!(PaymentToken::next_asset_id(), 1);
+		assert_ok!(PaymentToken::create_asset(Origin::signed(5), 1000, 0));
+		assert_eq!(PaymentToken::next_asset_id(), 2);
+		assert_eq!(PaymentToken::token_owner(1), Some(5));
+		assert_eq!(PaymentToken::total_supply(1), 1000);
+		assert_eq!(PaymentToken::free_balance(1, 5), 1000);
+
+		//the minimum balance is set along with the asset, and the owner must hold at least that much
+		assert_noop!(PaymentToken::create_asset(Origin::signed(5), 99, 100), Error::<TestRuntime>::BelowMinimumBalance);
+		assert_ok!(PaymentToken::create_asset(Origin::signed(5), 1000, 100));
+		assert_eq!(PaymentToken::minimum_balance(2), 100);
+		assert_noop!(PaymentToken::transfer_from(Origin::signed(5), 2, 6, 99), Error::<TestRuntime>::BelowMinimumBalance);
+	})
+}
+
+#[test]
+fn assets_are_isolated() {
+	build_ext().execute_with(|| {
+		assert_ok!(PaymentToken::create_asset(Origin::signed(5), 1000, 0));
+		//each asset has its own owner
+		assert_noop!(PaymentToken::mint(Origin::signed(1), 1, 5, 100), Error::<TestRuntime>::MissingRole);
+		assert_noop!(PaymentToken::mint(Origin::signed(5), 0, 5, 100), Error::<TestRuntime>::MissingRole);
+		assert_noop!(PaymentToken::mint(Origin::signed(5), 2, 5, 100), Error::<TestRuntime>::AssetNotExist);
+
+		//transfer of one asset leaves the others untouched
+		assert_ok!(PaymentToken::transfer_from(Origin::signed(5), 1, 2, 400));
+		assert_eq!(PaymentToken::free_balance(1, 5), 600);
+		assert_eq!(PaymentToken::free_balance(1, 2), 400);
+		assert_eq!(PaymentToken::free_balance(0, 2), 0);
+		assert_eq!(PaymentToken::total_supply(0), 21000000);
+		assert_eq!(PaymentToken::total_supply(1), 1000);
+	})
+}
+
+#[test]
+fn vesting_locks_transfer() {
+	//20000000 locked, 1000000 unlocked per block from block 10, nothing before block 15
+	build_ext_with_vesting(vec![(0, 1, 20000000, 1000000, 10, 15)]).execute_with(|| {
+		assert_noop!(PaymentToken::transfer_from(Origin::signed(1), 0, 2, 1000001), Error::<TestRuntime>::LiquidityRestrictions);
+		assert_ok!(PaymentToken::transfer_from(Origin::signed(1), 0, 2, 1000000));
+
+		//still before the cliff
+		System::set_block_number(14);
+		assert_noop!(PaymentToken::transfer_from(Origin::signed(1), 0, 2, 1), Error::<TestRuntime>::LiquidityRestrictions);
+
+		//5 blocks worth of vesting are unlocked at the cliff
+		System::set_block_number(15);
+		assert_noop!(PaymentToken::transfer_from(Origin::signed(1), 0, 2, 5000001), Error::<TestRuntime>::LiquidityRestrictions);
+		assert_ok!(PaymentToken::transfer_from(Origin::signed(1), 0, 2, 5000000));
+		assert_eq!(PaymentToken::free_balance(0, 2), 6000000);
+	})
+}
+
+#[test]
+fn vesting_locks_burn() {
+	build_ext_with_vesting(vec![(0, 1, 20000000, 1000000, 10, 15)]).execute_with(|| {
+		assert_noop!(PaymentToken::burn_own(Origin::signed(1), 0, 1000001), Error::<TestRuntime>::LiquidityRestrictions);
+		assert_noop!(PaymentToken::burn(Origin::signed(1), 0, 1, 1000001), Error::<TestRuntime>::LiquidityRestrictions);
+		assert_ok!(PaymentToken::burn_own(Origin::signed(1), 0, 1000000));
+		assert_eq!(PaymentToken::total_supply(0), 20000000);
+	})
+}
+
+#[test]
+fn vest_ok() {
+	build_ext_with_vesting(vec![(0, 1, 20000000, 1000000, 10, 15)]).execute_with(|| {
+		assert_noop!(PaymentToken::vest(Origin::signed(2), 0), Error::<TestRuntime>::NotVesting);
+
+		System::set_block_number(20);
+		assert_ok!(PaymentToken::vest(Origin::signed(1), 0));
+		assert!(PaymentToken::vesting(0, 1).is_some());
+
+		//fully vested, schedule is removed
+		System::set_block_number(30);
+		assert_ok!(PaymentToken::vest(Origin::signed(1), 0));
+		assert_eq!(PaymentToken::vesting(0, 1), None);
+		assert_ok!(PaymentToken::transfer_from(Origin::signed(1), 0, 2, 21000000));
+	})
+}
+
+#[test]
+fn named_reserve_ok() {
+	build_ext().execute_with(|| {
+		assert_ok!(PaymentToken::set_balance(Origin::signed(1), 0, 5, 1000, 2000));
+		assert_ok!(PaymentToken::reserve_token(Origin::signed(1), 0, 5, DEPOSIT, 300));
+		assert_ok!(PaymentToken::reserve_token(Origin::signed(1), 0, 5, BOND, 200));
+		assert_eq!(PaymentToken::named_reserve_of(0, (5, DEFAULT_RESERVE)), 2000);
+		assert_eq!(PaymentToken::named_reserve_of(0, (5, DEPOSIT)), 300);
+		assert_eq!(PaymentToken::named_reserve_of(0, (5, BOND)), 200);
+		assert_eq!(PaymentToken::reserved_balance(0, 5), 2500);
+		assert_eq!(PaymentToken::free_balance(0, 5), 500);
+
+		//can't unreserve more than what is held under that tag
+		assert_noop!(PaymentToken::unreserve_token(Origin::signed(1), 0, 5, DEPOSIT, 301), Error::<TestRuntime>::AmountTooLow);
+		assert_ok!(PaymentToken::unreserve_token(Origin::signed(1), 0, 5, DEPOSIT, 300));
+		assert_eq!(PaymentToken::named_reserve_of(0, (5, DEPOSIT)), 0);
+		assert_eq!(PaymentToken::reserved_balance(0, 5), 2200);
+		assert_eq!(PaymentToken::free_balance(0, 5), 800);
+
+		//set_balance only rewrites the untagged reserve
+		assert_noop!(PaymentToken::set_balance(Origin::signed(1), 0, 5, 800, 199), Error::<TestRuntime>::AmountTooLow);
+		assert_ok!(PaymentToken::set_balance(Origin::signed(1), 0, 5, 800, 500));
+		assert_eq!(PaymentToken::named_reserve_of(0, (5, DEFAULT_RESERVE)), 300);
+		assert_eq!(PaymentToken::named_reserve_of(0, (5, BOND)), 200);
+	})
+}
+
+#[test]
+fn slash_reserved_ok() {
+	build_ext().execute_with(|| {
+		assert_ok!(PaymentToken::set_balance(Origin::signed(1), 0, 5, 1000, 0));
+		assert_ok!(PaymentToken::reserve_token(Origin::signed(1), 0, 5, BOND, 600));
+		assert_eq!(PaymentToken::total_supply(0), 21001000);
+
+		assert_noop!(PaymentToken::slash_reserved(Origin::signed(5), 0, 5, BOND, 100), Error::<TestRuntime>::OnlyOwnerCanOperate);
+		assert_noop!(PaymentToken::slash_reserved(Origin::signed(1), 0, 5, DEPOSIT, 100), Error::<TestRuntime>::AmountTooLow);
+		assert_ok!(PaymentToken::slash_reserved(Origin::signed(1), 0, 5, BOND, 100));
+		assert_eq!(PaymentToken::named_reserve_of(0, (5, BOND)), 500);
+		assert_eq!(PaymentToken::reserved_balance(0, 5), 500);
+		assert_eq!(PaymentToken::free_balance(0, 5), 400);
+		assert_eq!(PaymentToken::total_supply(0), 21000900);
+	})
+}
+
+#[test]
+fn repatriate_reserved_ok() {
+	build_ext().execute_with(|| {
+		assert_ok!(PaymentToken::set_balance(Origin::signed(1), 0, 5, 1000, 0));
+		assert_ok!(PaymentToken::reserve_token(Origin::signed(1), 0, 5, DEPOSIT, 600));
+
+		assert_noop!(PaymentToken::repatriate_reserved(Origin::signed(1), 0, 5, DEPOSIT, 2, 601), Error::<TestRuntime>::AmountTooLow);
+		assert_ok!(PaymentToken::repatriate_reserved(Origin::signed(1), 0, 5, DEPOSIT, 2, 400));
+		assert_eq!(PaymentToken::named_reserve_of(0, (5, DEPOSIT)), 200);
+		assert_eq!(PaymentToken::reserved_balance(0, 5), 200);
+		assert_eq!(PaymentToken::free_balance(0, 5), 400);
+		assert_eq!(PaymentToken::free_balance(0, 2), 400);
+		assert_eq!(PaymentToken::total_supply(0), 21001000);
+	})
+}
+
+#[test]
+fn currency_transfer_ok() {
+	build_ext().execute_with(|| {
+		assert_eq!(Rkx::total_issuance(), 21000000);
+		assert_eq!(Rkx::free_balance(&1), 21000000);
+		assert_ok!(Rkx::transfer(&1, &5, 1000, ExistenceRequirement::AllowDeath));
+		assert_eq!(PaymentToken::free_balance(0, 5), 1000);
+		assert_eq!(Rkx::total_balance(&5), 1000);
+	})
+}
+
+#[test]
+fn currency_imbalances_update_total_supply() {
+	build_ext().execute_with(|| {
+		//deposit creates funds, supply follows once the imbalance is dropped
+		let _ = Rkx::deposit_creating(&5, 1000);
+		assert_eq!(PaymentToken::total_supply(0), 21001000);
+
+		let _ = Rkx::withdraw(&5, 400, WithdrawReasons::all(), ExistenceRequirement::AllowDeath);
+		assert_eq!(PaymentToken::free_balance(0, 5), 600);
+		assert_eq!(PaymentToken::total_supply(0), 21000600);
+
+		let (_, remaining) = Rkx::slash(&5, 700);
+		assert_eq!(remaining, 100);
+		assert_eq!(PaymentToken::total_supply(0), 21000000);
+	})
+}
+
+#[test]
+fn reservable_currency_ok() {
+	build_ext().execute_with(|| {
+		assert_ok!(PaymentToken::set_balance(Origin::signed(1), 0, 5, 1000, 0));
+		assert_ok!(PaymentToken::reserve_token(Origin::signed(1), 0, 5, DEPOSIT, 200));
+
+		assert!(Rkx::can_reserve(&5, 800));
+		assert!(!Rkx::can_reserve(&5, 801));
+		assert_ok!(Rkx::reserve(&5, 500));
+		assert_eq!(Rkx::reserved_balance(&5), 700);
+		assert_eq!(PaymentToken::named_reserve_of(0, (5, DEFAULT_RESERVE)), 500);
+
+		//only the untagged reserve is reachable through the currency traits
+		assert_eq!(Rkx::unreserve(&5, 600), 100);
+		assert_eq!(Rkx::reserved_balance(&5), 200);
+		assert_eq!(Rkx::free_balance(&5), 800);
+
+		assert_ok!(Rkx::reserve(&5, 300));
+		assert_eq!(Rkx::repatriate_reserved(&5, &2, 100), Ok(0));
+		assert_eq!(Rkx::free_balance(&2), 100);
+		let (_, remaining) = Rkx::slash_reserved(&5, 300);
+		assert_eq!(remaining, 100);
+		assert_eq!(Rkx::reserved_balance(&5), 200);
+		assert_eq!(PaymentToken::total_supply(0), 21000800);
+	})
+}
+
+#[test]
+fn lockable_currency_ok() {
+	build_ext().execute_with(|| {
+		assert_ok!(PaymentToken::set_balance(Origin::signed(1), 0, 5, 1000, 0));
+		Rkx::set_lock(STAKING, &5, 600, 10, WithdrawReasons::all());
+		assert_noop!(PaymentToken::transfer_from(Origin::signed(5), 0, 2, 401), Error::<TestRuntime>::LiquidityRestrictions);
+		assert_noop!(PaymentToken::burn_own(Origin::signed(5), 0, 401), Error::<TestRuntime>::LiquidityRestrictions);
+		assert_ok!(PaymentToken::transfer_from(Origin::signed(5), 0, 2, 100));
+
+		Rkx::extend_lock(STAKING, &5, 800, 10, WithdrawReasons::all());
+		assert_noop!(PaymentToken::transfer_from(Origin::signed(5), 0, 2, 101), Error::<TestRuntime>::LiquidityRestrictions);
+
+		//lock expires on its own
+		System::set_block_number(10);
+		assert_ok!(PaymentToken::transfer_from(Origin::signed(5), 0, 2, 101));
+
+		System::set_block_number(1);
+		Rkx::set_lock(STAKING, &5, 799, 10, WithdrawReasons::all());
+		Rkx::remove_lock(STAKING, &5);
+		assert_ok!(PaymentToken::transfer_from(Origin::signed(5), 0, 2, 799));
+		assert_eq!(PaymentToken::locks(0, 5), vec![]);
+	})
+}
+
+#[test]
+fn transfer_ownership_ok() {
+	build_ext().execute_with(|| {
+		assert_noop!(PaymentToken::transfer_ownership(Origin::signed(5), 0, 5), Error::<TestRuntime>::OnlyOwnerCanOperate);
+		assert_ok!(PaymentToken::transfer_ownership(Origin::signed(1), 0, 2));
+		assert_eq!(PaymentToken::token_owner(0), Some(2));
+
+		//the old owner lost every right over the asset
+		assert_noop!(PaymentToken::mint(Origin::signed(1), 0, 1, 100), Error::<TestRuntime>::MissingRole);
+		assert_noop!(PaymentToken::set_balance(Origin::signed(1), 0, 5, 1000, 0), Error::<TestRuntime>::OnlyOwnerCanOperate);
+		assert_ok!(PaymentToken::mint(Origin::signed(2), 0, 2, 100));
+	})
+}
+
+#[test]
+fn grant_revoke_role_ok() {
+	build_ext().execute_with(|| {
+		assert_noop!(PaymentToken::grant_role(Origin::signed(5), 0, Role::Minter, 5), Error::<TestRuntime>::OnlyOwnerCanOperate);
+		assert_ok!(PaymentToken::grant_role(Origin::signed(1), 0, Role::Minter, 5));
+		assert!(PaymentToken::has_role(0, (Role::Minter, 5)));
+		assert_ok!(PaymentToken::mint(Origin::signed(5), 0, 2, 1000));
+		assert_ok!(PaymentToken::burn(Origin::signed(5), 0, 2, 400));
+		assert_eq!(PaymentToken::free_balance(0, 2), 600);
+
+		//another role doesn't allow minting
+		assert_ok!(PaymentToken::grant_role(Origin::signed(1), 0, Role::Pauser, 3));
+		assert_noop!(PaymentToken::mint(Origin::signed(3), 0, 3, 1000), Error::<TestRuntime>::MissingRole);
+
+		assert_noop!(PaymentToken::revoke_role(Origin::signed(5), 0, Role::Minter, 5), Error::<TestRuntime>::OnlyOwnerCanOperate);
+		assert_ok!(PaymentToken::revoke_role(Origin::signed(1), 0, Role::Minter, 5));
+		assert_noop!(PaymentToken::mint(Origin::signed(5), 0, 2, 1000), Error::<TestRuntime>::MissingRole);
+	})
+}
+
+#[test]
+fn pause_ok() {
+	build_ext().execute_with(|| {
+		assert_ok!(PaymentToken::set_balance(Origin::signed(1), 0, 5, 1000, 0));
+		assert_ok!(Rkx::reserve(&5, 200));
+		assert_noop!(PaymentToken::pause(Origin::signed(5), 0), Error::<TestRuntime>::MissingRole);
+		assert_ok!(PaymentToken::grant_role(Origin::signed(1), 0, Role::Pauser, 3));
+		assert_ok!(PaymentToken::pause(Origin::signed(3), 0));
+		assert!(PaymentToken::is_paused(0));
+
+		assert_noop!(PaymentToken::transfer_from(Origin::signed(5), 0, 2, 100), Error::<TestRuntime>::AssetPaused);
+		assert_noop!(PaymentToken::reserve_token(Origin::signed(1), 0, 5, DEPOSIT, 100), Error::<TestRuntime>::AssetPaused);
+		assert_noop!(PaymentToken::unreserve_token(Origin::signed(1), 0, 5, DEPOSIT, 100), Error::<TestRuntime>::AssetPaused);
+		assert_noop!(
+			PaymentToken::repatriate_reserved(Origin::signed(1), 0, 5, DEPOSIT, 2, 100),
+			Error::<TestRuntime>::AssetPaused
+		);
+		//nor can it be moved through the currency traits
+		assert_noop!(Rkx::transfer(&5, &2, 100, ExistenceRequirement::AllowDeath), Error::<TestRuntime>::AssetPaused);
+		assert_eq!(
+			Rkx::withdraw(&5, 100, WithdrawReasons::all(), ExistenceRequirement::AllowDeath).err(),
+			Some(DispatchError::from(Error::<TestRuntime>::AssetPaused))
+		);
+		//nothing is unreserved, all of it is reported back
+		assert_eq!(Rkx::unreserve(&5, 100), 100);
+		assert_eq!(Rkx::reserved_balance(&5), 200);
+
+		assert_ok!(PaymentToken::unpause(Origin::signed(3), 0));
+		assert_ok!(PaymentToken::transfer_from(Origin::signed(5), 0, 2, 100));
+		assert_eq!(Rkx::unreserve(&5, 100), 0);
+		assert_eq!(Rkx::reserved_balance(&5), 100);
+	})
+}
+
+#[test]
+fn freeze_account_ok() {
+	build_ext().execute_with(|| {
+		assert_ok!(PaymentToken::set_balance(Origin::signed(1), 0, 5, 1000, 0));
+		assert_ok!(Rkx::reserve(&5, 200));
+		assert_noop!(PaymentToken::freeze_account(Origin::signed(5), 0, 5), Error::<TestRuntime>::MissingRole);
+		assert_ok!(PaymentToken::grant_role(Origin::signed(1), 0, Role::Freezer, 3));
+		assert_ok!(PaymentToken::freeze_account(Origin::signed(3), 0, 5));
+
+		//frozen account can neither send nor receive
+		assert_noop!(PaymentToken::transfer_from(Origin::signed(5), 0, 2, 100), Error::<TestRuntime>::AccountFrozen);
+		assert_noop!(PaymentToken::transfer_from(Origin::signed(1), 0, 5, 100), Error::<TestRuntime>::AccountFrozen);
+		assert_noop!(PaymentToken::reserve_token(Origin::signed(1), 0, 5, DEPOSIT, 100), Error::<TestRuntime>::AccountFrozen);
+		assert_eq!(
+			Rkx::withdraw(&5, 100, WithdrawReasons::all(), ExistenceRequirement::AllowDeath).err(),
+			Some(DispatchError::from(Error::<TestRuntime>::AccountFrozen))
+		);
+		assert_eq!(Rkx::deposit_into_existing(&5, 100).err(), Some(DispatchError::from(Error::<TestRuntime>::AccountFrozen)));
+		let _ = Rkx::deposit_creating(&5, 100);
+		assert_eq!(PaymentToken::free_balance(0, 5), 800);
+		assert_eq!(Rkx::unreserve(&5, 100), 100);
+		assert_eq!(Rkx::reserved_balance(&5), 200);
+		//other accounts are unaffected
+		assert_ok!(PaymentToken::transfer_from(Origin::signed(1), 0, 2, 100));
+
+		assert_ok!(PaymentToken::thaw_account(Origin::signed(3), 0, 5));
+		assert_ok!(PaymentToken::transfer_from(Origin::signed(5), 0, 2, 100));
+		assert_eq!(PaymentToken::free_balance(0, 2), 200);
+	})
+}
+
+#[test]
+fn set_minimum_balance_owner_ok() {
+	build_ext().execute_with(|| {
+		assert_noop!(PaymentToken::set_minimum_balance(Origin::signed(5), 0, 100), Error::<TestRuntime>::OnlyOwnerCanOperate);
+		assert_ok!(PaymentToken::set_minimum_balance(Origin::signed(1), 0, 100));
+		assert_eq!(PaymentToken::minimum_balance(0), 100);
+		assert_eq!(Rkx::minimum_balance(), 100);
+	})
+}
+
+#[test]
+fn transfer_below_minimum_balance_rejected() {
+	build_ext_with_minimum_balance(100).execute_with(|| {
+		//a new account must receive at least the minimum balance
+		assert_noop!(PaymentToken::transfer_from(Origin::signed(1), 0, 5, 99), Error::<TestRuntime>::BelowMinimumBalance);
+		assert_noop!(PaymentToken::mint(Origin::signed(1), 0, 5, 99), Error::<TestRuntime>::BelowMinimumBalance);
+		assert_ok!(PaymentToken::transfer_from(Origin::signed(1), 0, 5, 100));
+
+		//an existing account may receive less
+		assert_ok!(PaymentToken::transfer_from(Origin::signed(1), 0, 5, 1));
+		assert_eq!(PaymentToken::free_balance(0, 5), 101);
+	})
+}
+
+#[test]
+fn reap_dust_account_ok() {
+	build_ext_with_minimum_balance(100).execute_with(|| {
+		assert_ok!(PaymentToken::transfer_from(Origin::signed(1), 0, 5, 150));
+		assert_ok!(PaymentToken::transfer_from(Origin::signed(5), 0, 2, 100));
+
+		//the 50 left is burned and the account removed
+		assert!(!Balance::<TestRuntime>::exists(0, 5));
+		assert_eq!(PaymentToken::free_balance(0, 5), 0);
+		assert_eq!(PaymentToken::total_supply(0), 21000000 - 50);
+		assert_eq!(dust_removed(), vec![(0, 5, 50)]);
+	})
+}
+
+#[test]
+fn reap_zero_balance_account_ok() {
+	build_ext().execute_with(|| {
+		assert_ok!(PaymentToken::transfer_from(Origin::signed(1), 0, 5, 150));
+		assert_ok!(PaymentToken::transfer_from(Origin::signed(5), 0, 2, 150));
+		assert!(!Balance::<TestRuntime>::exists(0, 5));
+		assert_noop!(PaymentToken::transfer_from(Origin::signed(5), 0, 2, 1), Error::<TestRuntime>::AccountNotExist);
+		assert_eq!(PaymentToken::total_supply(0), 21000000);
+	})
+}
+
+#[test]
+fn reserve_keeps_account_alive() {
+	build_ext_with_minimum_balance(100).execute_with(|| {
+		assert_ok!(PaymentToken::transfer_from(Origin::signed(1), 0, 5, 1000));
+		assert_ok!(PaymentToken::reserve_token(Origin::signed(1), 0, 5, DEPOSIT, 950));
+		assert_ok!(PaymentToken::transfer_from(Origin::signed(5), 0, 1, 50));
+		assert_eq!(PaymentToken::reserved_balance(0, 5), 950);
+
+		//once the reserve is gone, the dust account is reaped
+		assert_ok!(PaymentToken::slash_reserved(Origin::signed(1), 0, 5, DEPOSIT, 950));
+		assert!(!Balance::<TestRuntime>::exists(0, 5));
+		assert_eq!(dust_removed(), vec![(0, 5, 0)]);
+	})
+}
+
+#[test]
+fn reap_skips_locked_account() {
+	build_ext_with_minimum_balance(100).execute_with(|| {
+		assert_ok!(PaymentToken::transfer_from(Origin::signed(1), 0, 5, 1000));
+		assert_ok!(Rkx::reserve(&5, 50));
+		Rkx::set_lock(STAKING, &5, 1000, 10, WithdrawReason::Reserve.into());
+
+		//both the free balance and the untagged reserve are below the minimum balance, but the lock holds
+		assert_ok!(PaymentToken::transfer_from(Origin::signed(5), 0, 2, 900));
+		assert!(Balance::<TestRuntime>::exists(0, 5));
+		assert_eq!(PaymentToken::free_balance(0, 5), 50);
+		assert_eq!(dust_removed(), vec![]);
+
+		//once the lock expired the dust account is reaped, the lock is left to the pallet that set it
+		System::set_block_number(10);
+		assert_ok!(PaymentToken::transfer_from(Origin::signed(5), 0, 2, 10));
+		assert!(!Balance::<TestRuntime>::exists(0, 5));
+		assert!(!Reserved::<TestRuntime>::exists(0, 5));
+		assert_eq!(PaymentToken::named_reserve_of(0, (5, DEFAULT_RESERVE)), 0);
+		assert_eq!(PaymentToken::total_supply(0), 21000000 - 90);
+		assert_eq!(dust_removed(), vec![(0, 5, 90)]);
+		assert_eq!(PaymentToken::locks(0, 5).len(), 1);
+	})
+}
+
+#[test]
+fn reap_skips_vesting_account() {
+	build_ext_with_minimum_balance(100).execute_with(|| {
+		assert_ok!(PaymentToken::transfer_from(Origin::signed(1), 0, 5, 1000));
+		Vesting::<TestRuntime>::insert(0, 5, VestingSchedule { locked: 80, per_block: 1, starting_block: 0, cliff: 100 });
+
+		//the 80 still vesting isn't burned
+		assert_ok!(PaymentToken::transfer_from(Origin::signed(5), 0, 2, 920));
+		assert_eq!(PaymentToken::free_balance(0, 5), 80);
+		assert_eq!(PaymentToken::total_supply(0), 21000000);
+		assert_eq!(dust_removed(), vec![]);
+	})
+}
+
+#[test]
+fn currency_keep_alive_ok() {
+	build_ext_with_minimum_balance(100).execute_with(|| {
+		assert_ok!(Rkx::transfer(&1, &5, 1000, ExistenceRequirement::AllowDeath));
+		assert_noop!(Rkx::transfer(&5, &2, 950, ExistenceRequirement::KeepAlive), Error::<TestRuntime>::BelowMinimumBalance);
+		assert!(Rkx::withdraw(&5, 950, WithdrawReasons::all(), ExistenceRequirement::KeepAlive).is_err());
+
+		//a deposit too small to open an account is dropped
+		let _ = Rkx::deposit_creating(&6, 99);
+		assert_eq!(Rkx::total_balance(&6), 0);
+		assert_eq!(Rkx::total_issuance(), 21000000);
+	})
+}
+
+#[test]
+fn batch_transfer_ok() {
+	build_ext().execute_with(|| {
+		assert_ok!(PaymentToken::batch_transfer(Origin::signed(1), 0, vec![(2, 100), (3, 200), (2, 50), (1, 500)]));
+		assert_eq!(PaymentToken::free_balance(0, 2), 150);
+		assert_eq!(PaymentToken::free_balance(0, 3), 200);
+		assert_eq!(PaymentToken::free_balance(0, 1), 21000000 - 350);
+	})
+}
+
+#[test]
+fn batch_transfer_all_or_nothing() {
+	build_ext_with_minimum_balance(100).execute_with(|| {
+		assert_ok!(PaymentToken::transfer_from(Origin::signed(1), 0, 5, 1000));
+
+		//not enough for the whole batch
+		assert_noop!(
+			PaymentToken::batch_transfer(Origin::signed(5), 0, vec![(2, 600), (3, 600)]),
+			Error::<TestRuntime>::AmountTooLow
+		);
+		//one recipient would be opened below the minimum balance
+		assert_noop!(
+			PaymentToken::batch_transfer(Origin::signed(5), 0, vec![(2, 600), (3, 50)]),
+			Error::<TestRuntime>::BelowMinimumBalance
+		);
+		//one recipient is frozen
+		assert_ok!(PaymentToken::freeze_account(Origin::signed(1), 0, 3));
+		assert_noop!(
+			PaymentToken::batch_transfer(Origin::signed(5), 0, vec![(2, 600), (3, 100)]),
+			Error::<TestRuntime>::AccountFrozen
+		);
+
+		//a new recipient listed twice only has to reach the minimum balance in total
+		assert_noop!(
+			PaymentToken::batch_transfer(Origin::signed(5), 0, vec![(4, 50), (4, 40)]),
+			Error::<TestRuntime>::BelowMinimumBalance
+		);
+		assert_ok!(PaymentToken::batch_transfer(Origin::signed(5), 0, vec![(6, 60), (6, 40)]));
+		assert_eq!(PaymentToken::free_balance(0, 6), 100);
+
+		//the sender may drop below the minimum balance mid-batch, it's only reaped at the end
+		assert_ok!(PaymentToken::batch_transfer(Origin::signed(5), 0, vec![(2, 500), (4, 350)]));
+		assert_eq!(PaymentToken::free_balance(0, 4), 350);
+		assert!(!Balance::<TestRuntime>::exists(0, 5));
+	})
+}
+
+#[test]
+fn batch_weight_scales_with_length() {
+	let one = Call::<TestRuntime>::batch_transfer(0, vec![(2, 1)]).get_dispatch_info().weight;
+	let ten = Call::<TestRuntime>::batch_transfer(0, vec![(2, 1); 10]).get_dispatch_info().weight;
+	assert_eq!(ten - one, 9 * 2_000);
+	assert_eq!(Call::<TestRuntime>::airdrop(0, vec![]).get_dispatch_info().weight, 10_000);
+}
+
+#[test]
+fn airdrop_ok() {
+	build_ext().execute_with(|| {
+		assert_noop!(PaymentToken::airdrop(Origin::signed(5), 0, vec![(2, 100)]), Error::<TestRuntime>::OnlyOwnerCanOperate);
+		assert_ok!(PaymentToken::airdrop(Origin::signed(1), 0, vec![(2, 100), (3, 200)]));
+		assert_eq!(PaymentToken::free_balance(0, 2), 100);
+		assert_eq!(PaymentToken::free_balance(0, 3), 200);
+		assert_eq!(PaymentToken::total_supply(0), 21000300);
+	})
+}
+
+#[test]
+fn metadata_ok() {
+	build_ext().execute_with(|| {
+		assert_eq!(PaymentToken::metadata(0), AssetMetadata {
+			name: b"RKX Token".to_vec(),
+			symbol: b"RKX".to_vec(),
+			decimals: 12,
+		});
+
+		assert_noop!(
+			PaymentToken::set_metadata(Origin::signed(5), 0, b"RockX".to_vec(), b"RKX".to_vec(), 18),
+			Error::<TestRuntime>::OnlyOwnerCanOperate
+		);
+		assert_noop!(
+			PaymentToken::set_metadata(Origin::signed(1), 0, vec![b'a'; MAX_METADATA_LENGTH + 1], b"RKX".to_vec(), 18),
+			Error::<TestRuntime>::MetadataTooLong
+		);
+		assert_ok!(PaymentToken::set_metadata(Origin::signed(1), 0, b"RockX".to_vec(), b"RKX".to_vec(), 18));
+		assert_eq!(PaymentToken::metadata(0).decimals, 18);
+	})
+}
+
+#[test]
+fn transfer_with_memo_ok() {
+	build_ext().execute_with(|| {
+		assert_noop!(
+			PaymentToken::transfer_with_memo(Origin::signed(1), 0, 2, 100, vec![0; MAX_MEMO_LENGTH + 1]),
+			Error::<TestRuntime>::MemoTooLong
+		);
+		assert_noop!(
+			PaymentToken::transfer_with_memo(Origin::signed(1), 0, 1, 100, b"order-42".to_vec()),
+			Error::<TestRuntime>::TransferToSelf
+		);
+		assert_noop!(
+			PaymentToken::transfer_with_memo(Origin::signed(2), 0, 1, 100, b"order-42".to_vec()),
+			Error::<TestRuntime>::AccountNotExist
+		);
+		//a zero transfer pays nothing to match an order against
+		assert_noop!(
+			PaymentToken::transfer_with_memo(Origin::signed(1), 0, 3, 0, b"order-41".to_vec()),
+			Error::<TestRuntime>::AmountTooLow
+		);
+
+		assert_ok!(PaymentToken::transfer_with_memo(Origin::signed(1), 0, 2, 100, b"order-42".to_vec()));
+		assert_eq!(PaymentToken::free_balance(0, 2), 100);
+		assert_eq!(PaymentToken::free_balance(0, 1), 21000000 - 100);
+	})
+}
+
+#[test]
+fn create_stream_ok() {
+	build_ext().execute_with(|| {
+		System::set_block_number(5);
+		assert_noop!(PaymentToken::create_stream(Origin::signed(1), 0, 2, 10, 4, 20), Error::<TestRuntime>::InvalidStreamPeriod);
+		assert_noop!(PaymentToken::create_stream(Origin::signed(1), 0, 2, 10, 20, 20), Error::<TestRuntime>::InvalidStreamPeriod);
+		assert_noop!(PaymentToken::create_stream(Origin::signed(1), 0, 2, 0, 10, 20), Error::<TestRuntime>::AmountTooLow);
+
+		assert_ok!(PaymentToken::create_stream(Origin::signed(1), 0, 2, 10, 10, 20));
+		assert_eq!(PaymentToken::next_stream_id(), 1);
+		assert_eq!(PaymentToken::named_reserve_of(0, (1, STREAM)), 100);
+		assert_eq!(PaymentToken::free_balance(0, 1), 21000000 - 100);
 	})
-}
\ No newline at end of file
+}
+
+#[test]
+fn withdraw_from_stream_ok() {
+	build_ext().execute_with(|| {
+		assert_ok!(PaymentToken::create_stream(Origin::signed(1), 0, 2, 10, 10, 20));
+
+		//nothing accrues before the start
+		System::set_block_number(10);
+		assert_ok!(PaymentToken::withdraw_from_stream(Origin::signed(2), 0));
+		assert_eq!(PaymentToken::free_balance(0, 2), 0);
+
+		System::set_block_number(13);
+		assert_noop!(PaymentToken::withdraw_from_stream(Origin::signed(1), 0), Error::<TestRuntime>::NotStreamPayee);
+		assert_ok!(PaymentToken::withdraw_from_stream(Origin::signed(2), 0));
+		assert_eq!(PaymentToken::free_balance(0, 2), 30);
+		assert_eq!(PaymentToken::stream(0).unwrap().withdrawn, 30);
+
+		//nothing accrues after the end, and the stream is done once paid out
+		System::set_block_number(30);
+		assert_ok!(PaymentToken::withdraw_from_stream(Origin::signed(2), 0));
+		assert_eq!(PaymentToken::free_balance(0, 2), 100);
+		assert_eq!(PaymentToken::reserved_balance(0, 1), 0);
+		assert_eq!(PaymentToken::stream(0), None);
+	})
+}
+
+#[test]
+fn cancel_stream_ok() {
+	build_ext().execute_with(|| {
+		assert_ok!(PaymentToken::create_stream(Origin::signed(1), 0, 2, 10, 10, 20));
+		System::set_block_number(12);
+		assert_ok!(PaymentToken::withdraw_from_stream(Origin::signed(2), 0));
+
+		System::set_block_number(16);
+		assert_noop!(PaymentToken::cancel_stream(Origin::signed(3), 0), Error::<TestRuntime>::NotStreamParty);
+		assert_ok!(PaymentToken::cancel_stream(Origin::signed(1), 0));
+
+		//6 blocks paid to the payee, the other 4 back to the payer
+		assert_eq!(PaymentToken::free_balance(0, 2), 60);
+		assert_eq!(PaymentToken::free_balance(0, 1), 21000000 - 60);
+		assert_eq!(PaymentToken::reserved_balance(0, 1), 0);
+		assert_eq!(PaymentToken::stream(0), None);
+	})
+}
+
+#[test]
+fn cancel_stream_frozen_payer_moves_nothing() {
+	build_ext().execute_with(|| {
+		assert_ok!(PaymentToken::create_stream(Origin::signed(1), 0, 2, 10, 10, 20));
+		System::set_block_number(16);
+		assert_ok!(PaymentToken::freeze_account(Origin::signed(1), 0, 1));
+
+		//the refund to the frozen payer fails, so the payee isn't paid either
+		assert_noop!(PaymentToken::cancel_stream(Origin::signed(2), 0), Error::<TestRuntime>::AccountFrozen);
+		assert_eq!(PaymentToken::free_balance(0, 2), 0);
+
+		//what accrued can still be withdrawn only once
+		assert_ok!(PaymentToken::thaw_account(Origin::signed(1), 0, 1));
+		assert_ok!(PaymentToken::cancel_stream(Origin::signed(2), 0));
+		assert_eq!(PaymentToken::free_balance(0, 2), 60);
+		assert_eq!(PaymentToken::named_reserve_of(0, (1, STREAM)), 0);
+	})
+}
+
+#[test]
+fn snapshot_ok() {
+	build_ext().execute_with(|| {
+		assert_noop!(PaymentToken::snapshot(Origin::signed(2), 0), Error::<TestRuntime>::OnlyOwnerCanOperate);
+		assert_ok!(PaymentToken::snapshot(Origin::signed(1), 0));
+		assert_eq!(PaymentToken::current_snapshot(0), 1);
+
+		//no such snapshots
+		assert_eq!(PaymentToken::balance_at(0, 1, 0), None);
+		assert_eq!(PaymentToken::balance_at(0, 1, 2), None);
+		assert_eq!(PaymentToken::total_supply_at(0, 2), None);
+
+		//nothing is recorded until a balance changes
+		assert_eq!(PaymentToken::balance_at(0, 1, 1), Some(21000000));
+		assert_eq!(PaymentToken::account_snapshot_count(0, 1), 0);
+	})
+}
+
+#[test]
+fn balance_at_ok() {
+	build_ext().execute_with(|| {
+		assert_ok!(PaymentToken::transfer_from(Origin::signed(1), 0, 2, 1000));
+		assert_ok!(PaymentToken::snapshot(Origin::signed(1), 0));
+		assert_ok!(PaymentToken::transfer_from(Origin::signed(1), 0, 2, 500));
+		assert_ok!(PaymentToken::transfer_from(Origin::signed(2), 0, 3, 200));
+
+		//only the balance before the first change is recorded
+		assert_eq!(PaymentToken::account_snapshot_count(0, 2), 1);
+		assert_eq!(PaymentToken::account_snapshot(0, (2, 0)), (1, 1000));
+		assert_eq!(PaymentToken::balance_at(0, 2, 1), Some(1000));
+		assert_eq!(PaymentToken::balance_at(0, 3, 1), Some(0));
+
+		//snapshots without changes in between resolve to the next recorded value
+		assert_ok!(PaymentToken::snapshot(Origin::signed(1), 0));
+		assert_ok!(PaymentToken::snapshot(Origin::signed(1), 0));
+		assert_ok!(PaymentToken::transfer_from(Origin::signed(2), 0, 3, 300));
+		assert_eq!(PaymentToken::balance_at(0, 2, 1), Some(1000));
+		assert_eq!(PaymentToken::balance_at(0, 2, 2), Some(1300));
+		assert_eq!(PaymentToken::balance_at(0, 2, 3), Some(1300));
+		assert_eq!(PaymentToken::balance_at(0, 3, 3), Some(200));
+		assert_eq!(PaymentToken::free_balance(0, 2), 1000);
+	})
+}
+
+#[test]
+fn balance_at_counts_reserved_ok() {
+	build_ext().execute_with(|| {
+		assert_ok!(PaymentToken::transfer_from(Origin::signed(1), 0, 2, 1000));
+		assert_ok!(PaymentToken::do_reserve(&0, &2, &DEPOSIT, 400));
+		assert_ok!(PaymentToken::snapshot(Origin::signed(1), 0));
+
+		//reserving doesn't change the total balance, repatriating does
+		assert_ok!(PaymentToken::do_unreserve(&0, &2, &DEPOSIT, 100));
+		assert_ok!(PaymentToken::do_repatriate_reserved(&0, &2, &DEPOSIT, &3, 300));
+		assert_eq!(PaymentToken::balance_at(0, 2, 1), Some(1000));
+		assert_eq!(PaymentToken::balance_at(0, 3, 1), Some(0));
+		assert_eq!(PaymentToken::total_balance(0, 2), 700);
+	})
+}
+
+#[test]
+fn total_supply_at_ok() {
+	build_ext().execute_with(|| {
+		assert_ok!(PaymentToken::snapshot(Origin::signed(1), 0));
+		assert_ok!(PaymentToken::mint(Origin::signed(1), 0, 2, 1000));
+		assert_ok!(PaymentToken::snapshot(Origin::signed(1), 0));
+		assert_ok!(PaymentToken::burn(Origin::signed(1), 0, 1, 500));
+
+		assert_eq!(PaymentToken::total_supply_at(0, 1), Some(21000000));
+		assert_eq!(PaymentToken::total_supply_at(0, 2), Some(21001000));
+		assert_eq!(PaymentToken::total_supply(0), 21000500);
+		assert_eq!(PaymentToken::balance_at(0, 2, 1), Some(0));
+		assert_eq!(PaymentToken::balance_at(0, 2, 2), Some(1000));
+	})
+}
+
+#[test]
+fn snapshot_through_currency_ok() {
+	build_ext().execute_with(|| {
+		assert_ok!(PaymentToken::snapshot(Origin::signed(1), 0));
+		assert_ok!(Rkx::transfer(&1, &5, 1000, ExistenceRequirement::AllowDeath));
+		drop(Rkx::issue(100));
+
+		assert_eq!(PaymentToken::balance_at(0, 1, 1), Some(21000000));
+		assert_eq!(PaymentToken::balance_at(0, 5, 1), Some(0));
+		assert_eq!(PaymentToken::total_supply_at(0, 1), Some(21000000));
+		assert_eq!(PaymentToken::total_supply(0), 21000100);
+	})
+}
+
+fn sign_permit(signer: u64, owner: u64, spender: u64, amount: u64, nonce: u64, deadline: u64) -> TestSignature {
+	TestSignature(signer, PaymentToken::permit_payload(&0, &owner, &spender, amount, nonce, deadline))
+}
+
+#[test]
+fn permit_ok() {
+	build_ext().execute_with(|| {
+		//submitted by someone else than the owner
+		assert_ok!(PaymentToken::permit(Origin::signed(3), 0, 1, 2, 1000, 10, sign_permit(1, 1, 2, 1000, 0, 10)));
+		assert_eq!(PaymentToken::allowance(0, (1, 2)), 1000);
+		assert_eq!(PaymentToken::permit_nonce(1), 1);
+
+		assert_ok!(PaymentToken::transfer_approved(Origin::signed(2), 0, 1, 2, 600));
+		assert_eq!(PaymentToken::free_balance(0, 2), 600);
+	})
+}
+
+#[test]
+fn permit_invalid_signature() {
+	build_ext().execute_with(|| {
+		//signed by someone else than the owner
+		assert_noop!(
+			PaymentToken::permit(Origin::signed(3), 0, 1, 2, 1000, 10, sign_permit(2, 1, 2, 1000, 0, 10)),
+			Error::<TestRuntime>::InvalidSignature
+		);
+		//signed for another amount
+		assert_noop!(
+			PaymentToken::permit(Origin::signed(3), 0, 1, 2, 2000, 10, sign_permit(1, 1, 2, 1000, 0, 10)),
+			Error::<TestRuntime>::InvalidSignature
+		);
+		//signed for another asset
+		assert_noop!(
+			PaymentToken::permit(Origin::signed(3), 1, 1, 2, 1000, 10, sign_permit(1, 1, 2, 1000, 0, 10)),
+			Error::<TestRuntime>::InvalidSignature
+		);
+	})
+}
+
+#[test]
+fn permit_replay_and_expiry() {
+	build_ext().execute_with(|| {
+		let signature = sign_permit(1, 1, 2, 1000, 0, 10);
+		assert_ok!(PaymentToken::permit(Origin::signed(3), 0, 1, 2, 1000, 10, signature.clone()));
+		assert_noop!(PaymentToken::permit(Origin::signed(3), 0, 1, 2, 1000, 10, signature), Error::<TestRuntime>::InvalidSignature);
+
+		//the next permit is signed with the next nonce
+		System::set_block_number(11);
+		assert_noop!(
+			PaymentToken::permit(Origin::signed(3), 0, 1, 2, 500, 10, sign_permit(1, 1, 2, 500, 1, 10)),
+			Error::<TestRuntime>::PermitExpired
+		);
+		assert_ok!(PaymentToken::permit(Origin::signed(3), 0, 1, 2, 500, 20, sign_permit(1, 1, 2, 500, 1, 20)));
+		assert_eq!(PaymentToken::allowance(0, (1, 2)), 500);
+	})
+}
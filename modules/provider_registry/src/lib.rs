@@ -0,0 +1,153 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+///provider registry module to handle
+/// profiles of hash power service providers (name, pool url hash, algorithms, declared capacity)
+/// approval and suspension of providers by the admin origin
+/// only approved providers may sell hash power contracts
+use codec::{Decode, Encode};
+use frame_support::{decl_error, decl_event, decl_module, decl_storage, dispatch::DispatchResult, ensure, traits::EnsureOrigin};
+use hashpower_contract::{ServiceProviders, ValueHashPower};
+use sp_runtime::RuntimeDebug;
+use sp_std::prelude::*;
+use system::ensure_signed;
+
+#[cfg(all(feature = "std", test))]
+mod mock;
+
+#[cfg(all(feature = "std", test))]
+mod tests;
+
+pub const MAX_NAME_LENGTH: usize = 64;
+pub const MAX_ALGORITHMS: usize = 8;
+pub const MAX_ALGORITHM_LENGTH: usize = 16;
+
+/// The pallet's configuration trait.
+pub trait Trait: system::Trait {
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+	/// Origin allowed to approve and suspend providers.
+	type ApproveOrigin: EnsureOrigin<Self::Origin>;
+}
+
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum ProviderStatus {
+	/// Registered, waiting for approval.
+	Pending,
+	/// Approved, may sell hash power.
+	Active,
+	/// Approval withdrawn.
+	Suspended,
+}
+
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Provider<Hash> {
+	pub name: Vec<u8>,
+	/// Hash of the url of the mining pool the provider points its hardware to.
+	pub pool_url_hash: Hash,
+	/// Names of the mining algorithms the provider's hardware runs, e.g. `SHA-256`.
+	pub algorithms: Vec<Vec<u8>>,
+	/// Total hash power the provider declares to operate.
+	pub capacity: ValueHashPower,
+	pub status: ProviderStatus,
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as ProviderRegistry {
+		Providers get(provider): map hasher(blake2_256) T::AccountId => Option<Provider<T::Hash>>;
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event() = default;
+
+		/// Register the sender as a provider, or replace their profile. Either way the provider
+		/// has to be approved again.
+		fn register(
+			origin,
+			name: Vec<u8>,
+			pool_url_hash: T::Hash,
+			algorithms: Vec<Vec<u8>>,
+			capacity: ValueHashPower
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(name.len() <= MAX_NAME_LENGTH, Error::<T>::NameTooLong);
+			ensure!(!algorithms.is_empty(), Error::<T>::NoAlgorithm);
+			ensure!(algorithms.len() <= MAX_ALGORITHMS, Error::<T>::TooManyAlgorithms);
+			ensure!(algorithms.iter().all(|a| a.len() <= MAX_ALGORITHM_LENGTH), Error::<T>::AlgorithmTooLong);
+			ensure!(capacity > 0, Error::<T>::ZeroCapacity);
+
+			<Providers<T>>::insert(&sender, Provider {
+				name,
+				pool_url_hash,
+				algorithms,
+				capacity,
+				status: ProviderStatus::Pending,
+			});
+			Self::deposit_event(RawEvent::ProviderRegistered(sender, capacity));
+			Ok(())
+		}
+
+		fn approve(origin, provider: T::AccountId) -> DispatchResult {
+			T::ApproveOrigin::ensure_origin(origin)?;
+
+			Self::set_status(&provider, ProviderStatus::Active)?;
+			Self::deposit_event(RawEvent::ProviderApproved(provider));
+			Ok(())
+		}
+
+		/// Stop a provider from selling hash power. Contracts already sold are not affected.
+		fn suspend(origin, provider: T::AccountId) -> DispatchResult {
+			T::ApproveOrigin::ensure_origin(origin)?;
+
+			Self::set_status(&provider, ProviderStatus::Suspended)?;
+			Self::deposit_event(RawEvent::ProviderSuspended(provider));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	fn set_status(provider: &T::AccountId, status: ProviderStatus) -> DispatchResult {
+		<Providers<T>>::mutate(provider, |p| match p {
+			Some(p) => {
+				p.status = status;
+				Ok(())
+			}
+			None => Err(Error::<T>::ProviderNotExist.into()),
+		})
+	}
+}
+
+impl<T: Trait> ServiceProviders<T::AccountId> for Module<T> {
+	fn is_active(who: &T::AccountId) -> bool {
+		Self::provider(who).map_or(false, |p| p.status == ProviderStatus::Active)
+	}
+
+	fn capacity(who: &T::AccountId) -> ValueHashPower {
+		Self::provider(who).map_or(0, |p| p.capacity)
+	}
+}
+
+decl_event!(
+	pub enum Event<T>
+	where
+		AccountId = <T as system::Trait>::AccountId,
+	{
+		/// A provider registered (provider, declared capacity), waiting for approval.
+		ProviderRegistered(AccountId, ValueHashPower),
+		ProviderApproved(AccountId),
+		ProviderSuspended(AccountId),
+	}
+);
+
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		NameTooLong,
+		/// A provider must run at least one algorithm
+		NoAlgorithm,
+		TooManyAlgorithms,
+		AlgorithmTooLong,
+		ZeroCapacity,
+		ProviderNotExist,
+	}
+}
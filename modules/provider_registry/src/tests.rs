@@ -0,0 +1,70 @@
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::{build_ext, Origin, ProviderRegistry, TestRuntime};
+use sp_core::H256;
+use sp_runtime::DispatchError;
+
+fn register(who: u64, capacity: ValueHashPower) -> DispatchResult {
+	ProviderRegistry::register(Origin::signed(who), b"rockx".to_vec(), H256::repeat_byte(1), vec![b"SHA-256".to_vec()], capacity)
+}
+
+#[test]
+fn register_ok() {
+	build_ext().execute_with(|| {
+		assert_noop!(register(1, 0), Error::<TestRuntime>::ZeroCapacity);
+		assert_noop!(
+			ProviderRegistry::register(Origin::signed(1), vec![b'a'; 65], H256::zero(), vec![b"SHA-256".to_vec()], 1000),
+			Error::<TestRuntime>::NameTooLong
+		);
+		assert_noop!(
+			ProviderRegistry::register(Origin::signed(1), b"rockx".to_vec(), H256::zero(), vec![], 1000),
+			Error::<TestRuntime>::NoAlgorithm
+		);
+		assert_noop!(
+			ProviderRegistry::register(Origin::signed(1), b"rockx".to_vec(), H256::zero(), vec![b"SHA-256".to_vec(); 9], 1000),
+			Error::<TestRuntime>::TooManyAlgorithms
+		);
+		assert_noop!(
+			ProviderRegistry::register(Origin::signed(1), b"rockx".to_vec(), H256::zero(), vec![vec![b'a'; 17]], 1000),
+			Error::<TestRuntime>::AlgorithmTooLong
+		);
+
+		assert_ok!(register(1, 1000));
+		let provider = ProviderRegistry::provider(1).unwrap();
+		assert_eq!(provider.capacity, 1000);
+		assert_eq!(ProviderRegistry::capacity(&1), 1000);
+		assert_eq!(provider.status, ProviderStatus::Pending);
+		//not active until approved
+		assert!(!ProviderRegistry::is_active(&1));
+	})
+}
+
+#[test]
+fn approve_ok() {
+	build_ext().execute_with(|| {
+		assert_noop!(ProviderRegistry::approve(Origin::ROOT, 1), Error::<TestRuntime>::ProviderNotExist);
+		assert_ok!(register(1, 1000));
+		assert_noop!(ProviderRegistry::approve(Origin::signed(1), 1), DispatchError::BadOrigin);
+
+		assert_ok!(ProviderRegistry::approve(Origin::ROOT, 1));
+		assert!(ProviderRegistry::is_active(&1));
+		assert!(!ProviderRegistry::is_active(&2));
+
+		//a new profile has to be approved again
+		assert_ok!(register(1, 2000));
+		assert!(!ProviderRegistry::is_active(&1));
+	})
+}
+
+#[test]
+fn suspend_ok() {
+	build_ext().execute_with(|| {
+		assert_ok!(register(1, 1000));
+		assert_ok!(ProviderRegistry::approve(Origin::ROOT, 1));
+		assert_noop!(ProviderRegistry::suspend(Origin::signed(2), 1), DispatchError::BadOrigin);
+
+		assert_ok!(ProviderRegistry::suspend(Origin::ROOT, 1));
+		assert_eq!(ProviderRegistry::provider(1).unwrap().status, ProviderStatus::Suspended);
+		assert!(!ProviderRegistry::is_active(&1));
+	})
+}
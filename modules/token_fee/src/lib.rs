@@ -0,0 +1,194 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+///token fee module to handle
+/// transaction fees paid in the payment token instead of the native currency
+/// conversion rate between the two currencies, set by governance
+/// the account token fees are routed to
+use codec::{Decode, Encode};
+use frame_support::{
+	decl_error, decl_event, decl_module, decl_storage,
+	dispatch::DispatchResult,
+	ensure,
+	traits::{Currency, ExistenceRequirement, WithdrawReason, WithdrawReasons},
+	weights::DispatchInfo,
+};
+use sp_runtime::{
+	traits::{SaturatedConversion, SignedExtension, Zero},
+	transaction_validity::{
+		InvalidTransaction, TransactionPriority, TransactionValidity, TransactionValidityError, ValidTransaction,
+	},
+};
+use sp_std::prelude::*;
+use system::ensure_root;
+use transaction_payment::ChargeTransactionPayment;
+
+#[cfg(all(feature = "std", test))]
+mod mock;
+
+#[cfg(all(feature = "std", test))]
+mod tests;
+
+type NativeBalanceOf<T> =
+	<<T as transaction_payment::Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+type TokenBalanceOf<T> = <<T as Trait>::FeeCurrency as Currency<<T as system::Trait>::AccountId>>::Balance;
+
+/// The pallet's configuration trait.
+pub trait Trait: transaction_payment::Trait {
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// The currency fees are taken in when the signer chooses to pay in tokens.
+	type FeeCurrency: Currency<Self::AccountId>;
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as TokenFee {
+		/// `(token, native)`: `token` units of the fee currency are worth `native` units of the
+		/// native currency. Paying fees in tokens is disabled while this is unset.
+		ConversionRate get(conversion_rate): Option<(TokenBalanceOf<T>, NativeBalanceOf<T>)>;
+		/// The account token fees are paid to. They are burned while this is unset.
+		FeeCollector get(fee_collector): Option<T::AccountId>;
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event() = default;
+
+		/// Set the rate fees are converted at: `token` units of the fee currency for `native` units
+		/// of the native currency.
+		fn set_conversion_rate(origin, token: TokenBalanceOf<T>, native: NativeBalanceOf<T>) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(!token.is_zero() && !native.is_zero(), Error::<T>::InvalidConversionRate);
+
+			<ConversionRate<T>>::put((token, native));
+			Self::deposit_event(RawEvent::ConversionRateSet(token, native));
+			Ok(())
+		}
+
+		/// Stop accepting fees in tokens.
+		fn disable_token_fees(origin) -> DispatchResult {
+			ensure_root(origin)?;
+
+			<ConversionRate<T>>::kill();
+			Self::deposit_event(RawEvent::TokenFeesDisabled);
+			Ok(())
+		}
+
+		/// Route token fees to `collector`.
+		fn set_fee_collector(origin, collector: T::AccountId) -> DispatchResult {
+			ensure_root(origin)?;
+
+			<FeeCollector<T>>::put(&collector);
+			Self::deposit_event(RawEvent::FeeCollectorSet(collector));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Convert a fee in the native currency to the fee currency, rounding up so that a non-zero
+	/// fee is never waived. `None` if paying in tokens is disabled.
+	pub fn token_fee(native_fee: NativeBalanceOf<T>) -> Option<TokenBalanceOf<T>> {
+		let (token, native) = Self::conversion_rate()?;
+		let token: u128 = token.saturated_into();
+		let native: u128 = native.saturated_into();
+		let native_fee: u128 = native_fee.saturated_into();
+
+		let fee = native_fee.saturating_mul(token).saturating_add(native - 1) / native;
+		Some(fee.saturated_into())
+	}
+
+	/// Take `fee` from `who` in the fee currency and hand it to the fee collector.
+	fn charge_token_fee(who: &T::AccountId, fee: TokenBalanceOf<T>, reasons: WithdrawReasons) -> DispatchResult {
+		let imbalance = T::FeeCurrency::withdraw(who, fee, reasons, ExistenceRequirement::KeepAlive)?;
+		match Self::fee_collector() {
+			Some(collector) => T::FeeCurrency::resolve_creating(&collector, imbalance),
+			None => drop(imbalance),
+		}
+		Ok(())
+	}
+}
+
+/// Replacement for `ChargeTransactionPayment`: charges the fee, plus `tip`, either in the native
+/// currency or, if `pay_in_token` is set, in the fee currency at the current conversion rate.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+pub struct ChargeTokenTransactionPayment<T: Trait + Send + Sync> {
+	#[codec(compact)]
+	tip: NativeBalanceOf<T>,
+	pay_in_token: bool,
+}
+
+impl<T: Trait + Send + Sync> ChargeTokenTransactionPayment<T> {
+	pub fn from(tip: NativeBalanceOf<T>, pay_in_token: bool) -> Self {
+		Self { tip, pay_in_token }
+	}
+}
+
+impl<T: Trait + Send + Sync> sp_std::fmt::Debug for ChargeTokenTransactionPayment<T> {
+	#[cfg(feature = "std")]
+	fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		write!(f, "ChargeTokenTransactionPayment<{:?}, {:?}>", self.tip, self.pay_in_token)
+	}
+	#[cfg(not(feature = "std"))]
+	fn fmt(&self, _: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		Ok(())
+	}
+}
+
+impl<T: Trait + Send + Sync> SignedExtension for ChargeTokenTransactionPayment<T>
+where
+	NativeBalanceOf<T>: Send + Sync,
+{
+	type AccountId = T::AccountId;
+	type Call = T::Call;
+	type AdditionalSigned = ();
+	type DispatchInfo = DispatchInfo;
+	type Pre = ();
+
+	fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+		Ok(())
+	}
+
+	fn validate(&self, who: &Self::AccountId, call: &Self::Call, info: Self::DispatchInfo, len: usize) -> TransactionValidity {
+		if !self.pay_in_token {
+			return ChargeTransactionPayment::<T>::from(self.tip).validate(who, call, info, len);
+		}
+
+		let fee = ChargeTransactionPayment::<T>::compute_fee(len as u32, info, self.tip);
+		let token_fee = Module::<T>::token_fee(fee).ok_or(InvalidTransaction::Payment)?;
+		if !token_fee.is_zero() {
+			let reasons = if self.tip.is_zero() {
+				WithdrawReason::TransactionPayment.into()
+			} else {
+				WithdrawReason::TransactionPayment | WithdrawReason::Tip
+			};
+			Module::<T>::charge_token_fee(who, token_fee, reasons).map_err(|_| InvalidTransaction::Payment)?;
+		}
+
+		let mut r = ValidTransaction::default();
+		// priority follows the fee in native terms, whichever currency it was paid in
+		r.priority = fee.saturated_into::<TransactionPriority>();
+		Ok(r)
+	}
+}
+
+decl_event!(
+	pub enum Event<T>
+	where
+		AccountId = <T as system::Trait>::AccountId,
+		TokenBalance = TokenBalanceOf<T>,
+		NativeBalance = NativeBalanceOf<T>,
+	{
+		ConversionRateSet(TokenBalance, NativeBalance),
+		TokenFeesDisabled,
+		FeeCollectorSet(AccountId),
+	}
+);
+
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// Neither side of the conversion rate may be zero
+		InvalidConversionRate,
+	}
+}
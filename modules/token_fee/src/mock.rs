@@ -0,0 +1,109 @@
+//mocks for token fee
+#[cfg(test)]
+use super::*;
+
+use frame_support::{impl_outer_origin, parameter_types, weights::Weight};
+use sp_core::H256;
+use sp_runtime::{
+	testing::{Header, TestSignature, UintAuthorityId},
+	traits::{BlakeTwo256, ConvertInto, IdentityLookup},
+	Perbill,
+};
+
+impl_outer_origin! {
+	pub enum Origin for TestRuntime {}
+}
+
+#[derive(Clone, Eq, PartialEq)]
+pub struct TestRuntime;
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+	pub const ExistentialDeposit: u64 = 0;
+	pub const TransferFee: u64 = 0;
+	pub const CreationFee: u64 = 0;
+	pub const TransactionBaseFee: u64 = 0;
+	pub const TransactionByteFee: u64 = 1;
+	pub const RkxAssetId: u32 = 0;
+	pub const StreamReserveId: [u8; 8] = *b"stream  ";
+}
+
+impl system::Trait for TestRuntime {
+	type Origin = Origin;
+	type Call = ();
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = ();
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type ModuleToIndex = ();
+}
+
+impl balances::Trait for TestRuntime {
+	type Balance = u64;
+	type OnFreeBalanceZero = ();
+	type OnNewAccount = ();
+	type Event = ();
+	type DustRemoval = ();
+	type TransferPayment = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type TransferFee = TransferFee;
+	type CreationFee = CreationFee;
+}
+
+impl transaction_payment::Trait for TestRuntime {
+	type Currency = balances::Module<TestRuntime>;
+	type OnTransactionPayment = ();
+	type TransactionBaseFee = TransactionBaseFee;
+	type TransactionByteFee = TransactionByteFee;
+	type WeightToFee = ConvertInto;
+	type FeeMultiplierUpdate = ();
+}
+
+impl payment_token::Trait for TestRuntime {
+	type Event = ();
+	type Balance = u64;
+	type AssetId = u32;
+	type BlockNumberToBalance = ConvertInto;
+	type ReserveIdentifier = [u8; 8];
+	type DustHandler = ();
+	type StreamReserveId = StreamReserveId;
+	type Signature = TestSignature;
+	type Signer = UintAuthorityId;
+}
+
+impl Trait for TestRuntime {
+	type Event = ();
+	type FeeCurrency = Rkx;
+}
+
+pub type TokenFee = Module<TestRuntime>;
+pub type Balances = balances::Module<TestRuntime>;
+pub type PaymentToken = payment_token::Module<TestRuntime>;
+pub type Rkx = payment_token::AssetCurrency<TestRuntime, RkxAssetId>;
+
+// Account 1 owns the whole RKX supply, accounts 1 and 2 hold the native currency.
+pub fn build_ext() -> sp_io::TestExternalities {
+	let mut t = system::GenesisConfig::default().build_storage::<TestRuntime>().unwrap();
+	balances::GenesisConfig::<TestRuntime> {
+		balances: vec![(1, 1000), (2, 1000)],
+		vesting: vec![],
+	}.assimilate_storage(&mut t).unwrap();
+	payment_token::GenesisConfig::<TestRuntime> {
+		assets: vec![(0, 1, 21000000)],
+		vesting: vec![],
+		minimum_balances: vec![],
+		metadata: vec![],
+	}.assimilate_storage(&mut t).unwrap();
+	t.into()
+}
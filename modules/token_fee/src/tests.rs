@@ -0,0 +1,83 @@
+use super::*;
+use frame_support::{assert_noop, assert_ok, weights::DispatchClass};
+use mock::{build_ext, Balances, Origin, PaymentToken, TestRuntime, TokenFee};
+use sp_runtime::DispatchError;
+
+// weight 5 plus 10 bytes at 1 per byte: a native fee of 15
+fn info() -> DispatchInfo {
+	DispatchInfo { weight: 5, class: DispatchClass::Normal, pays_fee: true }
+}
+
+fn charge(tip: u64, pay_in_token: bool) -> ChargeTokenTransactionPayment<TestRuntime> {
+	ChargeTokenTransactionPayment::from(tip, pay_in_token)
+}
+
+#[test]
+fn set_conversion_rate_root_ok() {
+	build_ext().execute_with(|| {
+		assert_noop!(TokenFee::set_conversion_rate(Origin::signed(1), 2, 1), DispatchError::BadOrigin);
+		assert_noop!(TokenFee::set_conversion_rate(Origin::ROOT, 0, 1), Error::<TestRuntime>::InvalidConversionRate);
+		assert_ok!(TokenFee::set_conversion_rate(Origin::ROOT, 2, 1));
+		assert_eq!(TokenFee::conversion_rate(), Some((2, 1)));
+
+		assert_ok!(TokenFee::disable_token_fees(Origin::ROOT));
+		assert_eq!(TokenFee::conversion_rate(), None);
+	})
+}
+
+#[test]
+fn token_fee_rounds_up_ok() {
+	build_ext().execute_with(|| {
+		assert_eq!(TokenFee::token_fee(15), None);
+		assert_ok!(TokenFee::set_conversion_rate(Origin::ROOT, 1, 4));
+		assert_eq!(TokenFee::token_fee(15), Some(4));
+		assert_eq!(TokenFee::token_fee(16), Some(4));
+		assert_eq!(TokenFee::token_fee(0), Some(0));
+	})
+}
+
+#[test]
+fn pay_in_native_ok() {
+	build_ext().execute_with(|| {
+		assert_ok!(charge(0, false).validate(&2, &(), info(), 10));
+		assert_eq!(Balances::free_balance(&2), 1000 - 15);
+	})
+}
+
+#[test]
+fn pay_in_token_ok() {
+	build_ext().execute_with(|| {
+		assert_ok!(TokenFee::set_conversion_rate(Origin::ROOT, 2, 1));
+		assert_ok!(TokenFee::set_fee_collector(Origin::ROOT, 9));
+
+		assert_ok!(charge(5, true).validate(&1, &(), info(), 10));
+		//native balance untouched, (15 + 5 tip) * 2 taken in RKX
+		assert_eq!(Balances::free_balance(&1), 1000);
+		assert_eq!(PaymentToken::free_balance(0, 1), 21000000 - 40);
+		assert_eq!(PaymentToken::free_balance(0, 9), 40);
+		assert_eq!(PaymentToken::total_supply(0), 21000000);
+	})
+}
+
+#[test]
+fn pay_in_token_without_collector_burns_ok() {
+	build_ext().execute_with(|| {
+		assert_ok!(TokenFee::set_conversion_rate(Origin::ROOT, 1, 1));
+
+		assert_ok!(charge(0, true).validate(&1, &(), info(), 10));
+		assert_eq!(PaymentToken::free_balance(0, 1), 21000000 - 15);
+		assert_eq!(PaymentToken::total_supply(0), 21000000 - 15);
+	})
+}
+
+#[test]
+fn pay_in_token_rejected() {
+	build_ext().execute_with(|| {
+		//token fees disabled
+		assert!(charge(0, true).validate(&1, &(), info(), 10).is_err());
+
+		//no RKX to pay with
+		assert_ok!(TokenFee::set_conversion_rate(Origin::ROOT, 1, 1));
+		assert!(charge(0, true).validate(&2, &(), info(), 10).is_err());
+	})
+}
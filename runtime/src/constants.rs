@@ -2,11 +2,18 @@
 
 /// Money matters.
 pub mod currency {
-	use crate::types::Balance;
+	use crate::types::{AssetId, Balance};
 
 	pub const DOLLARS: Balance = 1_000_000_000_000;
 	pub const CENTS: Balance = DOLLARS / 100;
 	pub const MILLICENTS: Balance = CENTS / 1_000;
+
+	/// Asset id of the RKX payment token.
+	pub const RKX_ASSET_ID: AssetId = 0;
+	/// Number of decimals of one RKX, matching `DOLLARS`.
+	pub const RKX_DECIMALS: u8 = 12;
+	/// Smallest RKX balance an account may hold.
+	pub const RKX_MINIMUM_BALANCE: Balance = CENTS;
 }
 
 /// Time.
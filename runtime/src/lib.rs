@@ -93,7 +93,7 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	spec_name: create_runtime_str!("mdao"),
 	impl_name: create_runtime_str!("mdao"),
 	authoring_version: 1,
-	spec_version: 1,
+	spec_version: 2,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
 };
@@ -234,14 +234,88 @@ impl sudo::Trait for Runtime {
 }
 
 
+parameter_types! {
+	pub const MinContractDuration: BlockNumber = DAYS;
+	pub const MaxContractDuration: BlockNumber = 3 * 365 * DAYS;
+	pub const MinContractHashPower: hashpower_contract::ValueHashPower = 1;
+	pub const MaxContractHashPower: hashpower_contract::ValueHashPower = 1_000_000;
+	pub const BlocksPerDay: BlockNumber = DAYS;
+	pub const MaxScheduledPerBlock: u32 = 100;
+}
+
 impl hashpower_contract::Trait for Runtime {
 	type Event = Event;
 	type Randomness = RandomnessCollectiveFlip;
+	type MinDuration = MinContractDuration;
+	type MaxDuration = MaxContractDuration;
+	type MinHashPower = MinContractHashPower;
+	type MaxHashPower = MaxContractHashPower;
+	type ServiceProviders = ProviderRegistry;
+	type Currency = RkxCurrency;
+	type BlocksPerDay = BlocksPerDay;
+	type MaxScheduledPerBlock = MaxScheduledPerBlock;
+	type Payees = RkxPayees;
+}
+
+/// Accounts frozen on RKX, or all of them while RKX is paused, can't be paid for contracts.
+pub struct RkxPayees;
+impl hashpower_contract::Payees<AccountId> for RkxPayees {
+	fn can_receive(who: &AccountId) -> bool {
+		PaymentToken::ensure_not_frozen(&RKX_ASSET_ID, who).is_ok()
+	}
+}
+
+impl provider_registry::Trait for Runtime {
+	type Event = Event;
+	type ApproveOrigin = system::EnsureRoot<AccountId>;
+}
+
+parameter_types! {
+	pub const RkxAssetId: AssetId = RKX_ASSET_ID;
+	pub const StreamReserveId: [u8; 8] = *b"stream  ";
 }
 
+/// The RKX payment token seen as a `Currency`, for pallets that expect a `T::Currency`.
+pub type RkxCurrency = payment_token::AssetCurrency<Runtime, RkxAssetId>;
+
 impl payment_token::Trait for Runtime {
 	type Event = Event;
 	type Balance = Balance;
+	type AssetId = AssetId;
+	type BlockNumberToBalance = ConvertInto;
+	type ReserveIdentifier = [u8; 8];
+	type DustHandler = ();
+	type StreamReserveId = StreamReserveId;
+	type Signature = Signature;
+	type Signer = <Signature as Verify>::Signer;
+}
+
+parameter_types! {
+	pub const EscrowReserveId: [u8; 8] = *b"escrow  ";
+}
+
+impl escrow::Trait for Runtime {
+	type Event = Event;
+	type EscrowReserveId = EscrowReserveId;
+}
+
+parameter_types! {
+	pub const DistributionReserveId: [u8; 8] = *b"dividend";
+	pub const DistributionDeposit: Balance = 100 * DOLLARS;
+	pub const DistributionClaimPeriod: BlockNumber = 90 * DAYS;
+}
+
+impl distribution::Trait for Runtime {
+	type Event = Event;
+	type ShareAssetId = RkxAssetId;
+	type DistributionReserveId = DistributionReserveId;
+	type CreationDeposit = DistributionDeposit;
+	type ClaimPeriod = DistributionClaimPeriod;
+}
+
+impl token_fee::Trait for Runtime {
+	type Event = Event;
+	type FeeCurrency = RkxCurrency;
 }
 
 construct_runtime!(
@@ -261,7 +335,11 @@ construct_runtime!(
 		RandomnessCollectiveFlip: randomness_collective_flip::{Module, Call, Storage},
 
 		HashPowerContract: hashpower_contract::{Module, Call, Storage, Event<T>},
+		ProviderRegistry: provider_registry::{Module, Call, Storage, Event<T>},
 		PaymentToken: payment_token::{Module, Call, Storage, Event<T>, Config<T>},
+		TokenFee: token_fee::{Module, Call, Storage, Event<T>},
+		Escrow: escrow::{Module, Call, Storage, Event<T>},
+		Distribution: distribution::{Module, Call, Storage, Event<T>},
 	}
 );
 
@@ -282,7 +360,7 @@ pub type SignedExtra = (
 	system::CheckEra<Runtime>,
 	system::CheckNonce<Runtime>,
 	system::CheckWeight<Runtime>,
-	transaction_payment::ChargeTransactionPayment<Runtime>,
+	token_fee::ChargeTokenTransactionPayment<Runtime>,
 );
 /// Unchecked extrinsic type as expected by this runtime.
 pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, Call, Signature, SignedExtra>;
@@ -370,4 +448,37 @@ impl_runtime_apis! {
 			Grandpa::grandpa_authorities()
 		}
 	}
+
+	impl payment_token_rpc_runtime_api::PaymentTokenApi<Block, AssetId, AccountId, Balance> for Runtime {
+		fn free_balance(asset_id: AssetId, who: AccountId) -> Balance {
+			PaymentToken::free_balance(asset_id, who)
+		}
+
+		fn reserved_balance(asset_id: AssetId, who: AccountId) -> Balance {
+			PaymentToken::reserved_balance(asset_id, who)
+		}
+
+		fn total_balance(asset_id: AssetId, who: AccountId) -> Balance {
+			PaymentToken::total_balance(asset_id, who)
+		}
+
+		fn total_supply(asset_id: AssetId) -> Balance {
+			PaymentToken::total_supply(asset_id)
+		}
+
+		fn token_owner(asset_id: AssetId) -> Option<AccountId> {
+			PaymentToken::token_owner(asset_id)
+		}
+
+		fn metadata(asset_id: AssetId) -> (Vec<u8>, Vec<u8>, u8) {
+			let metadata = PaymentToken::metadata(asset_id);
+			(metadata.name, metadata.symbol, metadata.decimals)
+		}
+	}
+
+	impl hashpower_contract_runtime_api::HashPowerContractApi<Block, AccountId, BlockNumber> for Runtime {
+		fn free_capacity(provider: AccountId, start: BlockNumber, end: BlockNumber) -> u64 {
+			HashPowerContract::free_capacity(&provider, start, end)
+		}
+	}
 }
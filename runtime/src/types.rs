@@ -33,6 +33,9 @@ pub type Hash = sp_core::H256;
 /// Digest item type.
 pub type DigestItem = generic::DigestItem<Hash>;
 
+/// Identifier of an asset in the payment token module.
+pub type AssetId = u32;
+
 pub type AuctionId = u32;
 
 pub type Share = u128;
@@ -1,7 +1,7 @@
 use grandpa_primitives::AuthorityId as GrandpaId;
 use mdao_runtime::{
 	AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig, IndicesConfig, Signature, SudoConfig,
-	SystemConfig, WASM_BINARY, PaymentTokenConfig, DOLLARS,
+	SystemConfig, WASM_BINARY, PaymentTokenConfig, DOLLARS, RKX_ASSET_ID, RKX_MINIMUM_BALANCE, RKX_DECIMALS,
 };
 use sc_service;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
@@ -149,9 +149,13 @@ fn testnet_genesis(
 			authorities: initial_authorities.iter().map(|x| (x.1.clone(), 1)).collect(),
 		}),
 		payment_token: Some(PaymentTokenConfig {
-			token_owner: get_account_id_from_seed::<sr25519::Public>("Alice"),
 			//total_supply: 20200311,
-			total_supply: 21000000 * DOLLARS,
+			assets: vec![(RKX_ASSET_ID, get_account_id_from_seed::<sr25519::Public>("Alice"), 21000000 * DOLLARS)],
+			// (asset, account, locked, unlocked per block, starting block, cliff)
+			vesting: vec![],
+			minimum_balances: vec![(RKX_ASSET_ID, RKX_MINIMUM_BALANCE)],
+			// (asset, name, symbol, decimals)
+			metadata: vec![(RKX_ASSET_ID, b"RKX".to_vec(), b"RKX".to_vec(), RKX_DECIMALS)],
 		}),
 	}
 }
@@ -13,6 +13,9 @@ use sp_inherents::InherentDataProviders;
 use std::sync::Arc;
 use std::time::Duration;
 
+/// The RPC extensions exposed by the full node on top of the substrate defaults.
+pub type RpcExtension = jsonrpc_core::IoHandler<sc_rpc::Metadata>;
+
 // Our native executor instance.
 native_executor_instance!(
 	pub Executor,
@@ -104,6 +107,13 @@ pub fn new_full<C: Send + Default + 'static>(
 		.with_finality_proof_provider(|client, backend| {
 			Ok(Arc::new(GrandpaFinalityProofProvider::new(backend, client)) as _)
 		})?
+		.with_rpc_extensions(|client, _pool, _backend, _fetcher, _remote_blockchain| -> Result<RpcExtension, _> {
+			use payment_token_rpc::{PaymentToken, PaymentTokenApi};
+
+			let mut io = jsonrpc_core::IoHandler::default();
+			io.extend_with(PaymentTokenApi::to_delegate(PaymentToken::new(client)));
+			Ok(io)
+		})?
 		.build()?;
 
 	if participates_in_consensus {